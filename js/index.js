@@ -10,11 +10,17 @@ import("../pkg").then((lib) => {
   let app = new lib.App();
   worker.onmessage = function (e) {
     if (e.data[0] == "init") {
+      // Stop the search on puzzles that would keep the worker busy.
+      const budget = { millis: 10000 };
       function solve(sudoku) {
-        worker.postMessage([sudoku]);
+        worker.postMessage([sudoku, budget]);
       }
       app.set_solver(solve);
       app.start();
+      // A puzzle in the url hash, optionally followed by variant rules.
+      if (location.hash.length > 1) {
+        app.load(decodeURIComponent(location.hash.slice(1)));
+      }
     } else if (e.data[0] == "solved") {
       console.log(e.data[1]);
       app.on_solve(e.data[1]);
@@ -2,8 +2,23 @@ onmessage = function (e) {
   if (e.data[0] == "init") {
     import("../pkg_solver").then((lib) => {
       onmessage = function (e) {
+        if (e.data[0] == "stream") {
+          lib.solve_stream(e.data[1], function (step) {
+            postMessage(["step", step]);
+            return true;
+          });
+          postMessage(["streamed"]);
+          return;
+        }
         performance.mark("perf_start");
-        let solve = lib.solve(e.data[0]);
+        let budget = e.data[1] || {};
+        let solve = lib.solve(
+          e.data[0],
+          true,
+          budget.advances,
+          budget.guesses,
+          budget.millis
+        );
         performance.mark("perf_stop");
         performance.measure("perf_measure", "perf_start", "perf_stop");
         let entries = performance.getEntriesByName("perf_measure");
@@ -1,4 +1,8 @@
-use solver::Sudoku;
+use solver::{
+    generate::{reduce, Target},
+    notation, Branching, Config, Generator, Grade, Grid, MultiGrid, Puzzle, Shuffle, Solver,
+    Sudoku, Symmetry,
+};
 use std::{
     fs::File,
     io::{prelude::*, BufReader},
@@ -6,24 +10,184 @@ use std::{
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let time = std::time::Instant::now();
-    if let Some(input) = std::env::args().nth(1) {
+    if let Some("--explain") = std::env::args().nth(1).as_deref() {
+        for input in std::env::args().skip(2) {
+            let puzzle: Puzzle = input.parse()?;
+            let solve = puzzle.sudoku.solve_steps_config(puzzle.config()?);
+            for (i, step) in solve.iter().enumerate() {
+                println!("{:>3}: {}", i, step.explain());
+            }
+        }
+    } else if let Some("--grid") = std::env::args().nth(1).as_deref() {
+        for input in std::env::args().skip(2) {
+            let grid: Grid = input.parse()?;
+            match grid.solve() {
+                Some(solved) => println!("{} {:?}", solved, grid.hardest()),
+                None => println!("{} has no solution", input),
+            }
+        }
+    } else if let Some("--samurai") = std::env::args().nth(1).as_deref() {
+        for input in std::env::args().skip(2) {
+            let multi: MultiGrid = input.parse()?;
+            let solve = multi.solve_steps();
+            for (i, step) in solve.iter().enumerate() {
+                println!("{:>3}: {}", i, step.explain());
+            }
+            let guessed = solve.iter().any(|s| s.solver == Solver::BackTrace);
+            match multi.solve() {
+                Some(solved) if guessed => println!("{} after guessing", solved),
+                Some(solved) => println!("{}", solved),
+                None => println!("{} has no solution", input),
+            }
+        }
+    } else if let Some("--generate") = std::env::args().nth(1).as_deref() {
+        let mut generator = Generator::default();
+        if let Some(clues) = std::env::args().nth(2) {
+            generator.clues = clues.parse()?;
+        }
+        let seed = std::env::args().nth(3).map_or(Ok(0), |s| s.parse())?;
+        let count = std::env::args().nth(4).map_or(Ok(1), |s| s.parse())?;
+        for seed in seed..seed + count {
+            generator.seed = seed;
+            println!("{}", generator.generate().as_string());
+        }
+    } else if let Some("--reduce") = std::env::args().nth(1).as_deref() {
+        // An optional symmetry comes before the puzzles.
+        let mut inputs = std::env::args().skip(2).peekable();
+        let symmetry = match inputs.peek().and_then(|name| symmetry(name)) {
+            Some(symmetry) => {
+                inputs.next();
+                symmetry
+            }
+            None => Symmetry::None,
+        };
+        for input in inputs {
+            match reduce(&Sudoku::from(&input), symmetry) {
+                Some(reduction) => println!(
+                    "{} removed {}: {}",
+                    reduction.sudoku.as_string(),
+                    reduction.removed.len(),
+                    notation::group(&reduction.removed)
+                ),
+                None => println!("{} has no unique solution", input),
+            }
+        }
+    } else if let Some("--canonical") = std::env::args().nth(1).as_deref() {
+        for input in std::env::args().skip(2) {
+            println!("{}", Sudoku::from(input).canonical().as_string());
+        }
+    } else if let Some("--find") = std::env::args().nth(1).as_deref() {
+        let name = std::env::args().nth(2).unwrap_or_default().to_lowercase();
+        let target = target(&name).ok_or(format!("unknown grade or technique: {}", name))?;
+        let generator = Generator {
+            seed: std::env::args().nth(3).map_or(Ok(0), |s| s.parse())?,
+            clues: 0,
+            ..Default::default()
+        };
+        match generator.find(target, 1000) {
+            Some(sudoku) => println!("{}", sudoku.as_string()),
+            None => println!("no puzzle found"),
+        }
+    } else if let Some(input) = std::env::args().nth(1) {
         let file = File::open(input)?;
         let reader = BufReader::new(file);
-        let mut sum = 0;
-        reader.lines().for_each(|input| {
-            if let Ok(input) = input {
-                if input.len() == 81 {
-                    let sudoku = Sudoku::from(input);
-                    let solve = sudoku.solve_steps();
-                    if let Some(step) = solve.iter().last() {
-                        sum += step.guesses_t;
-                    }
-                }
+        let sudokus: Vec<Sudoku> = reader
+            .lines()
+            .filter_map(|input| input.ok())
+            .filter(|input| input.len() == 81)
+            .map(Sudoku::from)
+            .collect();
+        let guesses = |config: Config| -> u32 {
+            sudokus
+                .iter()
+                .filter_map(|sudoku| {
+                    let solve = sudoku.solve_steps_config(config.clone());
+                    solve.iter().last().map(|step| step.guesses_t)
+                })
+                .sum()
+        };
+        println!("guesses total: {}", guesses(Config::default()));
+        for &branching in &[
+            Branching::Mrv,
+            Branching::HiddenSingle,
+            Branching::Degree,
+            Branching::First,
+        ] {
+            let total = guesses(Config {
+                branching,
+                ..Default::default()
+            });
+            println!("  {:?}: {}", branching, total);
+        }
+
+        // Sensitivity of the guess count to the search order.
+        if let Some(seeds) = std::env::args().nth(2) {
+            let seeds: u64 = seeds.parse()?;
+            let totals: Vec<u32> = (0..seeds)
+                .map(|seed| {
+                    guesses(Config {
+                        shuffle: Some(Shuffle::new(seed)),
+                        ..Default::default()
+                    })
+                })
+                .collect();
+            if let (Some(min), Some(max)) = (totals.iter().min(), totals.iter().max()) {
+                let mean = totals.iter().sum::<u32>() as f64 / totals.len() as f64;
+                println!(
+                    "shuffled over {} seeds: min {} mean {:.1} max {}",
+                    seeds, min, mean, max
+                );
             }
-        });
-        println!("guesses total: {}", sum);
+        }
     }
     println!("{:#?}", time.elapsed());
 
     Ok(())
 }
+
+fn symmetry(name: &str) -> Option<Symmetry> {
+    [
+        Symmetry::None,
+        Symmetry::Rotational,
+        Symmetry::Diagonal,
+        Symmetry::Mirror,
+    ]
+    .iter()
+    .find(|s| format!("{:?}", s).to_lowercase() == name.to_lowercase())
+    .copied()
+}
+
+fn target(name: &str) -> Option<Target> {
+    let grades = [
+        Grade::Easy,
+        Grade::Medium,
+        Grade::Hard,
+        Grade::Fiendish,
+        Grade::Extreme,
+    ];
+    let solvers = [
+        Solver::Single,
+        Solver::Elim,
+        Solver::Cage,
+        Solver::Innies,
+        Solver::Thermo,
+        Solver::Arrow,
+        Solver::Sandwich,
+        Solver::Edges,
+        Solver::Inequality,
+        Solver::Set,
+        Solver::XWing,
+        Solver::XYWing,
+        Solver::BackTrace,
+    ];
+    grades
+        .iter()
+        .find(|g| g.to_string().to_lowercase() == name)
+        .map(|&g| Target::Grade(g))
+        .or_else(|| {
+            solvers
+                .iter()
+                .find(|s| format!("{:?}", s).to_lowercase() == name)
+                .map(|&s| Target::Technique(s))
+        })
+}
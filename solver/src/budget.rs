@@ -0,0 +1,71 @@
+/// Reads the current time in milliseconds, on any fixed epoch.
+pub type Clock = fn() -> f64;
+
+/// Limits on the amount of work a single solve is allowed to perform.
+///
+/// Every limit is optional, an empty budget never runs out. The `deadline`
+/// is read from `clock`, without a clock it is never reached. There is no
+/// system clock on `wasm32-unknown-unknown`, pass one from the host there,
+/// such as `js_sys::Date::now`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Budget {
+    /// Maximum number of `Entry` advances.
+    pub advances: Option<u64>,
+    /// Maximum number of guesses over the whole search.
+    pub guesses: Option<u32>,
+    /// Time on `clock` after which solving stops.
+    pub deadline: Option<f64>,
+    /// Where the time for `deadline` is read, such as `js_sys::Date::now`.
+    pub clock: Option<Clock>,
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// A budget that runs out `duration` from now on the system clock.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn within(duration: std::time::Duration) -> Self {
+        Self {
+            deadline: Some(system_clock() + duration.as_secs_f64() * 1000.0),
+            clock: Some(system_clock),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.advances.is_none() && self.guesses.is_none() && self.deadline.is_none()
+    }
+
+    /// Checked before every advance, with the work spent so far.
+    pub(crate) fn exceeded(&self, advances: u64, guesses: u32) -> bool {
+        if let Some(max) = self.advances {
+            if advances >= max {
+                return true;
+            }
+        }
+        if let Some(max) = self.guesses {
+            if guesses > max {
+                return true;
+            }
+        }
+        if let (Some(deadline), Some(clock)) = (self.deadline, self.clock) {
+            if clock() >= deadline {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Milliseconds since the first time the system clock was read.
+#[cfg(not(target_arch = "wasm32"))]
+fn system_clock() -> f64 {
+    use std::time::Instant;
+
+    thread_local! {
+        static START: Instant = Instant::now();
+    }
+    START.with(|start| start.elapsed().as_secs_f64() * 1000.0)
+}
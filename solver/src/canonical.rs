@@ -0,0 +1,118 @@
+//! Canonical form of a sudoku under the validity preserving transformations.
+//!
+//! Two puzzles are equivalent when one can be turned into the other by
+//! transposing, permuting bands and stacks, permuting rows within a band and
+//! columns within a stack, and relabelling the digits. The canonical form is
+//! the lexicographically smallest grid over all 3,359,232 transformations,
+//! with digits relabelled in order of first appearance.
+
+use crate::{
+    transform::{line_permutations, Transform},
+    Sudoku,
+};
+
+/// The transform that takes `sudoku` to its canonical form.
+pub fn canonical_transform(sudoku: &Sudoku) -> Transform {
+    let grid = sudoku.inner();
+    let mut transposed = [0; 81];
+    for (i, cell) in transposed.iter_mut().enumerate() {
+        *cell = grid[9 * (i % 9) + i / 9];
+    }
+
+    let perms = line_permutations();
+    let mut best = [u8::MAX; 81];
+    let mut found = (false, 0, 0, [0; 10]);
+    let mut out = [0; 81];
+    for (transpose, grid) in [grid, &transposed[..]].iter().enumerate() {
+        for (r, rows) in perms.iter().enumerate() {
+            for (c, cols) in perms.iter().enumerate() {
+                let mut labels = [0; 10];
+                let mut next = 1;
+                let mut less = false;
+                for (i, cell) in out.iter_mut().enumerate() {
+                    let value = grid[9 * rows[i / 9] + cols[i % 9]] as usize;
+                    if value != 0 && labels[value] == 0 {
+                        labels[value] = next;
+                        next += 1;
+                    }
+                    *cell = labels[value];
+                    if !less {
+                        if *cell > best[i] {
+                            break;
+                        }
+                        less = *cell < best[i];
+                    }
+                }
+                if less {
+                    best = out;
+                    found = (transpose == 1, r, c, labels);
+                }
+            }
+        }
+    }
+
+    let (transpose, rows, cols, mut labels) = found;
+    // Digits that don't appear get the remaining labels.
+    let mut next = labels.iter().copied().max().unwrap_or(0) + 1;
+    let mut digits = [0; 9];
+    for (d, digit) in digits.iter_mut().enumerate() {
+        if labels[d + 1] == 0 {
+            labels[d + 1] = next;
+            next += 1;
+        }
+        *digit = labels[d + 1];
+    }
+    Transform::lines(transpose, perms[rows], perms[cols]).then(&Transform::relabel(digits))
+}
+
+/// The canonical representative of the equivalence class of `sudoku`.
+pub fn canonical(sudoku: &Sudoku) -> Sudoku {
+    canonical_transform(sudoku).apply(sudoku)
+}
+
+/// Whether `a` can be transformed into `b`.
+pub fn is_equivalent(a: &Sudoku, b: &Sudoku) -> bool {
+    canonical(a) == canonical(b)
+}
+
+impl Sudoku {
+    pub fn canonical(&self) -> Sudoku {
+        canonical(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, Sudoku};
+
+    use super::{canonical, canonical_transform, is_equivalent};
+
+    static SAMPLE: &str =
+        "...6..8....35.4...65..217...6..............5..7138..2...7.1.6.4.1.......9....3..7";
+
+    #[test]
+    fn canonical_equivalent() {
+        let sudoku = Sudoku::from(SAMPLE);
+        // Rotate by 90 degrees and swap the digits 1 and 9.
+        let mut rotated = Sudoku::default();
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            let value = match *sudoku.cell(cell) {
+                1 => 9,
+                9 => 1,
+                n => n,
+            };
+            rotated.set_cell(Cell::new(cell.col, 8 - cell.row), value);
+        }
+        assert_ne!(sudoku, rotated);
+        assert!(is_equivalent(&sudoku, &rotated));
+        assert_eq!(canonical(&sudoku), canonical(&canonical(&sudoku)));
+        let transform = canonical_transform(&rotated);
+        assert_eq!(rotated.transform(&transform), canonical(&sudoku));
+        assert_eq!(canonical(&sudoku).transform(&transform.inverse()), rotated);
+
+        let mut other = sudoku.clone();
+        other.set_cell(Cell::new(0, 0), 1);
+        assert!(!is_equivalent(&sudoku, &other));
+    }
+}
@@ -0,0 +1,303 @@
+//! Constraints between orthogonally adjacent cells: Kropki dots, XV and
+//! greater-than signs.
+//!
+//! A mark sits on the edge between two cells:
+//!
+//! - white dot, `white:r1c12`: the digits are consecutive
+//! - black dot, `black:r1c12`: one digit is double the other
+//! - X, `x:r1c12`: the digits add up to 10
+//! - V, `v:r1c12`: the digits add up to 5
+//!
+//! With the negative constraint, `negative:white,black`, two adjacent cells
+//! without a mark between them may not have the relation of any of the
+//! listed marks.
+//!
+//! A greater-than sign, `r1c1>r1c2` or `r1c2<r1c1`, points at the smaller
+//! digit.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    notation::{self, ParseError},
+    Cell, Sudoku,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Mark {
+    White,
+    Black,
+    X,
+    V,
+}
+
+impl Mark {
+    /// Whether digits `a` and `b` on either side of the mark satisfy it.
+    pub fn allows(&self, a: u8, b: u8) -> bool {
+        match self {
+            Mark::White => a.abs_diff(b) == 1,
+            Mark::Black => a == 2 * b || b == 2 * a,
+            Mark::X => a + b == 10,
+            Mark::V => a + b == 5,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Mark::White => "white",
+            Mark::Black => "black",
+            Mark::X => "x",
+            Mark::V => "v",
+        }
+    }
+}
+
+impl fmt::Display for Mark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Mark {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        [Mark::White, Mark::Black, Mark::X, Mark::V]
+            .iter()
+            .copied()
+            .find(|m| m.name() == input.to_lowercase())
+            .ok_or_else(|| ParseError::new(input))
+    }
+}
+
+/// A mark between two cells.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Edge {
+    pub a: Cell,
+    pub b: Cell,
+    pub mark: Mark,
+}
+
+impl Edge {
+    pub fn new(a: Cell, b: Cell, mark: Mark) -> Self {
+        Self { a, b, mark }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.a == cell || self.b == cell
+    }
+
+    /// The cell on the other side of `cell`.
+    pub fn other(&self, cell: Cell) -> Cell {
+        if self.a == cell {
+            self.b
+        } else {
+            self.a
+        }
+    }
+
+    /// Whether the mark lies between `a` and `b`, in either order.
+    pub fn joins(&self, a: Cell, b: Cell) -> bool {
+        (self.a, self.b) == (a, b) || (self.a, self.b) == (b, a)
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        is_adjacent(self.a, self.b)
+    }
+}
+
+/// `mark:cells`, for example `white:r1c12` or `x:r45c3`.
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.mark, notation::group(&[self.a, self.b]))
+    }
+}
+
+impl FromStr for Edge {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::new(input);
+        let (mark, cells) = input.split_once(':').ok_or_else(invalid)?;
+        let mut parsed = Vec::new();
+        for group in cells.split(',') {
+            parsed.extend(notation::parse_group(group)?);
+        }
+        match parsed[..] {
+            [a, b] => Ok(Edge::new(a, b, mark.parse()?)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A greater-than sign between two cells.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Inequality {
+    pub greater: Cell,
+    pub less: Cell,
+}
+
+impl Inequality {
+    pub fn new(greater: Cell, less: Cell) -> Self {
+        Self { greater, less }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.greater == cell || self.less == cell
+    }
+
+    /// The cell on the other side of `cell`.
+    pub fn other(&self, cell: Cell) -> Cell {
+        if self.greater == cell {
+            self.less
+        } else {
+            self.greater
+        }
+    }
+
+    /// Whether the sign lies between `a` and `b`, in either order.
+    pub fn joins(&self, a: Cell, b: Cell) -> bool {
+        (self.greater, self.less) == (a, b) || (self.greater, self.less) == (b, a)
+    }
+
+    /// Whether digit `a` at `cell` and `b` at the other cell satisfy the sign.
+    pub fn fits(&self, cell: Cell, a: u8, b: u8) -> bool {
+        if cell == self.greater {
+            a > b
+        } else {
+            a < b
+        }
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        is_adjacent(self.greater, self.less)
+    }
+
+    /// Whether `value` fits at `cell` given the digit placed across the sign.
+    pub fn allows(&self, sudoku: &Sudoku, cell: Cell, value: u8) -> bool {
+        if !self.contains(cell) {
+            return true;
+        }
+        match *sudoku.cell(self.other(cell)) {
+            0 if cell == self.greater => value > 1,
+            0 => value < 9,
+            placed => self.fits(cell, value, placed),
+        }
+    }
+}
+
+/// `greater>less`, for example `r1c1>r1c2`.
+impl fmt::Display for Inequality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}>{}", self.greater, self.less)
+    }
+}
+
+impl FromStr for Inequality {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some((greater, less)) = input.split_once('>') {
+            Ok(Inequality::new(greater.parse()?, less.parse()?))
+        } else if let Some((less, greater)) = input.split_once('<') {
+            Ok(Inequality::new(greater.parse()?, less.parse()?))
+        } else {
+            Err(ParseError::new(input))
+        }
+    }
+}
+
+pub(crate) fn is_adjacent(a: Cell, b: Cell) -> bool {
+    a.row.abs_diff(b.row) + a.col.abs_diff(b.col) == 1
+}
+
+/// The cells orthogonally next to `cell`.
+pub(crate) fn neighbours(cell: Cell) -> impl Iterator<Item = Cell> {
+    let (row, col) = (cell.row as isize, cell.col as isize);
+    [(-1, 0), (0, -1), (0, 1), (1, 0)]
+        .iter()
+        .map(move |(dr, dc)| (row + dr, col + dc))
+        .filter(|(r, c)| (0..9).contains(r) && (0..9).contains(c))
+        .map(|(r, c)| Cell::new(r as usize, c as usize))
+}
+
+/// Whether `value` at `cell` fits every mark around it, and none of the
+/// `negative` marks on unmarked edges, given the digits already placed.
+pub(crate) fn allows(
+    edges: &[Edge],
+    negative: &[Mark],
+    sudoku: &Sudoku,
+    cell: Cell,
+    value: u8,
+) -> bool {
+    if edges.is_empty() && negative.is_empty() {
+        return true;
+    }
+    neighbours(cell).all(|other| {
+        let placed = *sudoku.cell(other);
+        if placed == 0 {
+            return true;
+        }
+        match edges.iter().find(|e| e.joins(cell, other)) {
+            Some(edge) => edge.mark.allows(value, placed),
+            None => !negative.iter().any(|m| m.allows(value, placed)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, Sudoku};
+
+    use super::{allows, Edge, Inequality, Mark};
+
+    #[test]
+    fn edges_marks() {
+        assert!(Mark::White.allows(4, 5) && !Mark::White.allows(4, 6));
+        assert!(Mark::Black.allows(3, 6) && Mark::Black.allows(8, 4));
+        assert!(Mark::X.allows(3, 7) && !Mark::V.allows(3, 7));
+
+        let edge: Edge = "x:r1c12".parse().unwrap();
+        assert_eq!(edge, Edge::new(Cell::new(0, 0), Cell::new(0, 1), Mark::X));
+        assert_eq!(edge.to_string().parse::<Edge>(), Ok(edge));
+        assert!(edge.is_valid());
+        assert!(!"v:r1c13".parse::<Edge>().unwrap().is_valid());
+        assert!("dot:r1c12".parse::<Edge>().is_err());
+        assert!("white:r1c123".parse::<Edge>().is_err());
+
+        let mut sudoku = Sudoku::default();
+        sudoku.set_cell(Cell::new(0, 1), 3);
+        assert!(allows(&[edge], &[], &sudoku, Cell::new(0, 0), 7));
+        assert!(!allows(&[edge], &[], &sudoku, Cell::new(0, 0), 6));
+        // 7 next to an unmarked 8 breaks the negative white dot rule.
+        sudoku.set_cell(Cell::new(1, 0), 8);
+        assert!(!allows(
+            &[edge],
+            &[Mark::White],
+            &sudoku,
+            Cell::new(0, 0),
+            7
+        ));
+        assert!(allows(&[edge], &[Mark::Black], &sudoku, Cell::new(0, 0), 7));
+    }
+
+    #[test]
+    fn edges_inequality() {
+        let sign: Inequality = "r1c2<r1c1".parse().unwrap();
+        assert_eq!(sign, Inequality::new(Cell::new(0, 0), Cell::new(0, 1)));
+        assert_eq!(sign.to_string(), "r1c1>r1c2");
+        assert_eq!(sign.to_string().parse::<Inequality>(), Ok(sign));
+        assert!(sign.is_valid());
+        assert!(!"r1c1>r2c2".parse::<Inequality>().unwrap().is_valid());
+        assert!("r1c1=r1c2".parse::<Inequality>().is_err());
+
+        let mut sudoku = Sudoku::default();
+        assert!(!sign.allows(&sudoku, Cell::new(0, 0), 1));
+        assert!(!sign.allows(&sudoku, Cell::new(0, 1), 9));
+        sudoku.set_cell(Cell::new(0, 1), 5);
+        assert!(sign.allows(&sudoku, Cell::new(0, 0), 6));
+        assert!(!sign.allows(&sudoku, Cell::new(0, 0), 4));
+        assert!(sign.allows(&sudoku, Cell::new(2, 2), 1));
+    }
+}
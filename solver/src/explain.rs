@@ -0,0 +1,285 @@
+//! Human readable descriptions of solve steps.
+
+use std::fmt::{self, Display, Write};
+
+use crate::{
+    notation, util::Domain, Cell, CellMod, ModMarking, ModTarget, SolveStep, Solver, StateMod,
+};
+
+/// Describes a single [`SolveStep`] as a sentence, created by [`SolveStep::explain`].
+#[derive(Debug, Copy, Clone)]
+pub struct Explanation<'a> {
+    step: &'a SolveStep,
+}
+
+impl SolveStep {
+    pub fn explain(&self) -> Explanation<'_> {
+        Explanation { step: self }
+    }
+}
+
+impl Solver {
+    /// The common name of the technique.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Solver::Init => "Start",
+            Solver::BackTrace => "Guess",
+            Solver::Base => "Naked Single",
+            Solver::Single => "Hidden Single",
+            Solver::Elim => "Locked Candidates",
+            Solver::Cage => "Cage Combinations",
+            Solver::Innies => "Innies & Outies",
+            Solver::Thermo => "Thermometer",
+            Solver::Arrow => "Arrow",
+            Solver::Sandwich => "Sandwich",
+            Solver::Edges => "Kropki & XV",
+            Solver::Inequality => "Greater Than",
+            Solver::Set => "Naked Set",
+            Solver::XWing => "X-Wing",
+            Solver::XYWing => "XY-Wing",
+            Solver::Incomplete => "Incomplete",
+            Solver::Solved => "Solved",
+        }
+    }
+}
+
+impl<'a> Display for Explanation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let step = self.step;
+        let change = &step.change;
+        match step.solver {
+            Solver::Init => write!(f, "Initial state"),
+            Solver::Solved => write!(f, "Solved"),
+            Solver::Incomplete if !step.valid => write!(f, "The sudoku has no solution"),
+            Solver::Incomplete => write!(f, "No further deductions found"),
+            _ if !change.has_targets() => write!(f, "{}", step.solver.name()),
+            Solver::Base | Solver::BackTrace => {
+                write!(f, "{}: {}", step.solver.name(), list(&change.target))
+            }
+            Solver::Single => {
+                write!(f, "{}: ", step.solver.name())?;
+                for (i, target) in change.target.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", target)?;
+                    if let (ModTarget::Digit(value), Some(domain)) =
+                        (&target.target, domain(change, 0))
+                    {
+                        write!(f, " (only place for {} in {})", value, region(domain))?;
+                    }
+                }
+                Ok(())
+            }
+            Solver::Elim => {
+                let (from, to) = match (domain(change, 0), domain(change, 1)) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => {
+                        return write!(
+                            f,
+                            "{}: eliminates {}",
+                            step.solver.name(),
+                            list(&change.target)
+                        )
+                    }
+                };
+                let kind = if let Domain::Sqr(_) = from {
+                    "Pointing"
+                } else {
+                    "Claiming"
+                };
+                write!(
+                    f,
+                    "{} ({}): {} in {} is confined to {}, eliminates {}",
+                    step.solver.name(),
+                    kind,
+                    digits(&change.target),
+                    region(from),
+                    region(to),
+                    list(&change.target)
+                )
+            }
+            Solver::Cage | Solver::Thermo | Solver::Arrow | Solver::Edges | Solver::Inequality => {
+                write!(
+                    f,
+                    "{} {} eliminates {}",
+                    step.solver.name(),
+                    notation::group(&cells(&change.source)),
+                    list(&change.target)
+                )
+            }
+            Solver::Sandwich => {
+                write!(f, "{}", step.solver.name())?;
+                if let Some(domain) = domain(change, 0) {
+                    write!(f, " in {}", region(domain))?;
+                }
+                write!(f, " eliminates {}", list(&change.target))
+            }
+            Solver::Innies => {
+                let cells = cells(&change.source);
+                write!(f, "{}: {}", step.solver.name(), notation::group(&cells))?;
+                if let Some(domain) = domain(change, 0) {
+                    write!(f, " around {}", region(domain))?;
+                }
+                write!(f, " eliminates {}", list(&change.target))
+            }
+            Solver::Set => {
+                let name = match cells(&change.source).len() {
+                    2 => "Naked Pair",
+                    3 => "Naked Triple",
+                    4 => "Naked Quad",
+                    _ => step.solver.name(),
+                };
+                write!(f, "{} {{{}}}", name, digits(&change.source))?;
+                if let Some(domain) = domain(change, 0) {
+                    write!(f, " in {}", region(domain))?;
+                }
+                write!(
+                    f,
+                    " ({}) eliminates {}",
+                    notation::group(&cells(&change.source)),
+                    list(&change.target)
+                )
+            }
+            Solver::XWing => {
+                let lines = change.marks.iter().filter_map(|m| match m {
+                    ModMarking::Domain(d) => Some(*d),
+                    _ => None,
+                });
+                let kind = match domain(change, 0) {
+                    Some(Domain::Col(_)) => "columns",
+                    _ => "rows",
+                };
+                write!(
+                    f,
+                    "{} on {} in {} {} eliminates {}",
+                    step.solver.name(),
+                    digits(&change.target),
+                    kind,
+                    lines
+                        .map(|d| (index(d) + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    list(&change.target)
+                )
+            }
+            Solver::XYWing => {
+                let sources = cells(&change.source);
+                let described = sources.iter().map(|c| {
+                    let options = change
+                        .source
+                        .iter()
+                        .filter(|s| s.cell == *c)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    format!("{} {{{}}}", c, digits(&options))
+                });
+                let mut described = described.collect::<Vec<_>>().into_iter();
+                write!(
+                    f,
+                    "{}: pivot {}",
+                    step.solver.name(),
+                    described.next().unwrap_or_default()
+                )?;
+                write!(f, " with pincers {}", join(described))?;
+                write!(f, " eliminates {}", list(&change.target))
+            }
+        }
+    }
+}
+
+fn domain(change: &StateMod, n: usize) -> Option<Domain> {
+    change
+        .marks
+        .iter()
+        .filter_map(|m| match m {
+            ModMarking::Domain(d) => Some(*d),
+            _ => None,
+        })
+        .nth(n)
+}
+
+fn index(domain: Domain) -> usize {
+    match domain {
+        Domain::Sqr(n) | Domain::Row(n) | Domain::Col(n) | Domain::Diag(n) => n,
+    }
+}
+
+fn region(domain: Domain) -> String {
+    match domain {
+        Domain::Sqr(n) => format!("box {}", n + 1),
+        Domain::Row(n) => format!("row {}", n + 1),
+        Domain::Col(n) => format!("column {}", n + 1),
+        Domain::Diag(0) => "the main diagonal".to_string(),
+        Domain::Diag(_) => "the anti-diagonal".to_string(),
+    }
+}
+
+/// Candidates in [`notation`], separated by commas.
+fn list(mods: &[CellMod]) -> String {
+    join(mods.iter().map(CellMod::to_string))
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// Distinct cells in the order they first appear.
+fn cells(mods: &[CellMod]) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    for m in mods {
+        if !cells.contains(&m.cell) {
+            cells.push(m.cell);
+        }
+    }
+    cells
+}
+
+/// Distinct digits in ascending order, separated by commas.
+fn digits(mods: &[CellMod]) -> String {
+    let mut found = [false; 10];
+    for m in mods {
+        if let ModTarget::Digit(n) | ModTarget::Option(n) = m.target {
+            found[n as usize] = true;
+        }
+    }
+    let mut out = String::new();
+    for n in (1..=9).filter(|&n| found[n]) {
+        if !out.is_empty() {
+            out.push(',');
+        }
+        write!(out, "{}", n).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CellMod, Solver, Sudoku};
+
+    #[test]
+    fn explain_steps() {
+        let solve = Sudoku::from(
+            "9.4.728.....8.36..8..9.....6.9....1..83..7.....7.....22...385.....729..6...6.....",
+        )
+        .solve_steps();
+        let single = solve
+            .iter()
+            .find(|s| s.solver == Solver::Single)
+            .unwrap()
+            .explain()
+            .to_string();
+        assert!(single.starts_with("Hidden Single: r"), "{}", single);
+        assert!(single.contains("(only place for "), "{}", single);
+
+        // Placements and eliminations are written in RnCn notation.
+        let naked = solve.iter().find(|s| s.solver == Solver::Base).unwrap();
+        let text = naked.explain().to_string();
+        let placed = text.trim_start_matches("Naked Single: ").split(", ");
+        for m in placed {
+            let m: CellMod = m.parse().unwrap();
+            assert!(naked.change.is_target(m.cell), "{}", text);
+        }
+        assert_eq!(solve.end().explain().to_string(), "Solved");
+    }
+}
@@ -0,0 +1,284 @@
+//! Random puzzle generation.
+//!
+//! A full grid is found by solving an empty sudoku with a seeded guessing
+//! order, after which givens are removed while the solution stays unique.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{sudoku::Solution, Budget, Cell, Config, Grade, Shuffle, Solver, Sudoku};
+
+/// Small seedable pseudo random generator (SplitMix64).
+#[derive(Debug, Copy, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Symmetry {
+    None,
+    /// 180 degree rotation around the center.
+    #[default]
+    Rotational,
+    /// Reflection in the main diagonal.
+    Diagonal,
+    /// Reflection in the middle column.
+    Mirror,
+}
+
+impl Symmetry {
+    /// The cells that are given or removed together with `cell`.
+    pub fn orbit(&self, cell: Cell) -> Vec<Cell> {
+        let other = match self {
+            Symmetry::None => cell,
+            Symmetry::Rotational => Cell::new(8 - cell.row, 8 - cell.col),
+            Symmetry::Diagonal => Cell::new(cell.col, cell.row),
+            Symmetry::Mirror => Cell::new(cell.row, 8 - cell.col),
+        };
+        if other == cell {
+            vec![cell]
+        } else {
+            vec![cell, other]
+        }
+    }
+}
+
+/// The difficulty a generated puzzle should have.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Target {
+    Any,
+    Grade(Grade),
+    /// Sudoku Explainer rating in `min..=max`, only exact ratings count.
+    Se(f32, f32),
+    /// Needs this technique and nothing harder.
+    Technique(Solver),
+}
+
+impl Target {
+    pub fn matches(&self, sudoku: &Sudoku) -> bool {
+        match *self {
+            Target::Any => true,
+            Target::Grade(grade) => sudoku.rate().grade == grade,
+            Target::Se(min, max) => {
+                let rating = sudoku.se_rating();
+                rating.exact && rating.value >= min && rating.value <= max
+            }
+            Target::Technique(solver) => sudoku.rate().hardest == solver,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+pub struct Generator {
+    pub symmetry: Symmetry,
+    /// Removal stops once the puzzle has this many givens or fewer. The
+    /// result can have more when no further given can be removed.
+    pub clues: usize,
+    pub seed: u64,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            symmetry: Symmetry::default(),
+            clues: 24,
+            seed: 0,
+        }
+    }
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// A random complete grid.
+    pub fn grid(&self) -> Sudoku {
+        let config = Config {
+            solvers: vec![Solver::Single],
+            shuffle: Some(Shuffle::new(self.seed)),
+            ..Default::default()
+        };
+        match Sudoku::default().solve_config(config, &Budget::unlimited(), None) {
+            Solution::Complete(sudoku) => sudoku,
+            _ => unreachable!("an empty sudoku always has a solution"),
+        }
+    }
+
+    /// A random puzzle with a unique solution.
+    pub fn generate(&self) -> Sudoku {
+        let mut rng = Rng::new(self.seed);
+        let mut cells: Vec<Cell> = Sudoku::default().cells().collect();
+        rng.shuffle(&mut cells);
+        remove(self.grid(), cells, self.symmetry, self.clues).sudoku
+    }
+
+    /// Generates puzzles for consecutive seeds, starting at `seed`, until
+    /// one matches `target`. Gives up after `attempts` puzzles.
+    pub fn find(&self, target: Target, attempts: u64) -> Option<Sudoku> {
+        (self.seed..self.seed.wrapping_add(attempts))
+            .map(|seed| Generator { seed, ..*self }.generate())
+            .find(|sudoku| target.matches(sudoku))
+    }
+}
+
+/// The result of [`reduce`].
+#[derive(Debug, Clone)]
+pub struct Reduction {
+    pub sudoku: Sudoku,
+    /// The givens that were removed, in the order of removal.
+    pub removed: Vec<Cell>,
+}
+
+/// Removes givens from a unique puzzle until none can be removed without
+/// losing uniqueness. Cells related by `symmetry` are removed together.
+///
+/// Returns `None` when `sudoku` does not have a unique solution.
+pub fn reduce(sudoku: &Sudoku, symmetry: Symmetry) -> Option<Reduction> {
+    if !sudoku.is_unique() {
+        return None;
+    }
+    Some(remove(sudoku.clone(), sudoku.cells(), symmetry, 0))
+}
+
+/// Tries to remove the givens of `cells` in order, stopping at `clues`.
+///
+/// Uniqueness can only be lost by removing givens, so a given that can't be
+/// removed now can't be removed later either and a single pass is minimal.
+fn remove(
+    mut sudoku: Sudoku,
+    cells: impl IntoIterator<Item = Cell>,
+    symmetry: Symmetry,
+    clues: usize,
+) -> Reduction {
+    let mut count = sudoku.inner().iter().filter(|&&c| c != 0).count();
+    let mut removed = Vec::new();
+    for cell in cells {
+        if count <= clues {
+            break;
+        }
+        if *sudoku.cell(cell) == 0 {
+            continue;
+        }
+        let orbit: Vec<Cell> = symmetry
+            .orbit(cell)
+            .into_iter()
+            .filter(|&c| *sudoku.cell(c) != 0)
+            .collect();
+        let mut candidate = sudoku.clone();
+        for &c in &orbit {
+            candidate.set_cell(c, 0);
+        }
+        if candidate.is_unique() {
+            sudoku = candidate;
+            count -= orbit.len();
+            removed.extend(orbit);
+        }
+    }
+    Reduction { sudoku, removed }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, Solver, Sudoku};
+
+    use super::{reduce, Generator, Symmetry, Target};
+
+    #[test]
+    fn generate_grid() {
+        let grid = Generator::new(7).grid();
+        assert!(grid.inner().iter().all(|&c| c != 0));
+        assert!(grid.is_unique());
+        assert_ne!(grid, Generator::new(8).grid());
+    }
+
+    #[test]
+    fn generate_puzzle() {
+        let generator = Generator {
+            symmetry: Symmetry::Rotational,
+            clues: 30,
+            seed: 42,
+        };
+        let puzzle = generator.generate();
+        assert_eq!(puzzle, generator.generate());
+        assert!(puzzle.is_unique());
+        let clues = puzzle.inner().iter().filter(|&&c| c != 0).count();
+        assert!(clues <= 31, "{}", clues);
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            let other = Cell::new(8 - cell.row, 8 - cell.col);
+            assert_eq!(*puzzle.cell(cell) == 0, *puzzle.cell(other) == 0);
+        }
+        assert_ne!(puzzle, Sudoku::default());
+    }
+
+    #[test]
+    fn generate_target() {
+        let generator = Generator {
+            clues: 0,
+            ..Default::default()
+        };
+        let puzzle = generator
+            .find(Target::Technique(Solver::XYWing), 50)
+            .unwrap();
+        assert!(puzzle.is_unique());
+        assert_eq!(puzzle.rate().hardest, Solver::XYWing);
+        assert!(Target::Any.matches(&puzzle));
+    }
+
+    #[test]
+    fn generate_reduce() {
+        let sudoku = Sudoku::from(
+            "964572831172843659835961274629485713483217965517396482246138597358729146791654328",
+        );
+        let reduction = reduce(&sudoku, Symmetry::None).unwrap();
+        let puzzle = reduction.sudoku;
+        assert!(puzzle.is_unique());
+        assert_eq!(
+            puzzle.inner().iter().filter(|&&c| c == 0).count(),
+            reduction.removed.len()
+        );
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            if *puzzle.cell(cell) != 0 {
+                let mut less = puzzle.clone();
+                less.set_cell(cell, 0);
+                assert!(!less.is_unique());
+            }
+        }
+
+        let symmetric = reduce(&sudoku, Symmetry::Mirror).unwrap().sudoku;
+        assert!(symmetric.is_unique());
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            let other = Cell::new(cell.row, 8 - cell.col);
+            assert_eq!(*symmetric.cell(cell) == 0, *symmetric.cell(other) == 0);
+        }
+        assert!(reduce(&Sudoku::default(), Symmetry::None).is_none());
+    }
+}
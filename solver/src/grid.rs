@@ -0,0 +1,382 @@
+//! Sudoku grids of other sizes than 9×9: 4×4 with 2×2 boxes, 6×6 with boxes
+//! of two rows and three columns, 12×12 with boxes of three rows and four
+//! columns and the 16×16 hexadoku with 4×4 boxes.
+//!
+//! A [`Grid`] carries its own size. It is solved by the same technique
+//! pipeline as any [`Sudoku`], on a board of its size with the regions of
+//! [`Layout::sized`]. Digits above 9 are written `A` to `G`.
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rating::Rating,
+    sudoku::{Solution, SYMBOLS},
+    Budget, Cell, Config, Solve, Solver, Sudoku,
+};
+
+/// The shape of a grid, boxes of `box_rows` by `box_cols` cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Size {
+    pub box_rows: usize,
+    pub box_cols: usize,
+}
+
+impl Size {
+    pub const FOUR: Size = Size::new(2, 2);
+    pub const SIX: Size = Size::new(2, 3);
+    pub const NINE: Size = Size::new(3, 3);
+    pub const TWELVE: Size = Size::new(3, 4);
+    pub const SIXTEEN: Size = Size::new(4, 4);
+
+    pub const fn new(box_rows: usize, box_cols: usize) -> Self {
+        Self { box_rows, box_cols }
+    }
+
+    /// The number of digits, rows and columns.
+    pub fn n(&self) -> usize {
+        self.box_rows * self.box_cols
+    }
+
+    pub fn cells(&self) -> usize {
+        self.n() * self.n()
+    }
+
+    /// The supported size with `cells` cells.
+    pub fn from_cells(cells: usize) -> Option<Size> {
+        [
+            Size::FOUR,
+            Size::SIX,
+            Size::NINE,
+            Size::TWELVE,
+            Size::SIXTEEN,
+        ]
+        .iter()
+        .copied()
+        .find(|s| s.cells() == cells)
+    }
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Size::NINE
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Grid {
+    size: Size,
+    /// Digits in reading order, `0` for an empty cell.
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            cells: vec![0; size.cells()],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        self.cells[row * self.size.n() + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: u8) {
+        assert!(value as usize <= self.size.n());
+        let n = self.size.n();
+        self.cells[row * n + col] = value;
+    }
+
+    /// The grid in its one line text form.
+    pub fn as_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// The grid as a board of its size.
+    pub fn sudoku(&self) -> Sudoku {
+        Sudoku::from(self)
+    }
+
+    /// The default configuration on the layout of this size.
+    pub fn config(&self) -> Config {
+        self.sudoku().config()
+    }
+
+    /// The solution, when there is one.
+    pub fn solve(&self) -> Option<Grid> {
+        match self
+            .sudoku()
+            .solve_config(self.config(), &Budget::unlimited(), None)
+        {
+            Solution::Complete(solved) => Grid::try_from(solved).ok(),
+            _ => None,
+        }
+    }
+
+    /// The steps of the technique pipeline.
+    pub fn solve_steps(&self) -> Solve {
+        self.sudoku().solve_steps_config(self.config())
+    }
+
+    /// Counts solutions, stopping at `limit`.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.sudoku().solutions(self.config(), limit).len()
+    }
+
+    /// The hardest technique needed to solve the grid, see [`Rating`].
+    pub fn hardest(&self) -> Option<Solver> {
+        let solve = self.solve_steps();
+        match solve.iter().last()?.solver {
+            Solver::Solved => Some(Rating::from(&solve).hardest),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &value in &self.cells {
+            let symbol = match value {
+                0 => '.',
+                v => SYMBOLS[v as usize - 1] as char,
+            };
+            write!(f, "{}", symbol)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// No supported grid has this many cells.
+    Size(usize),
+    /// A symbol that isn't a digit of this size.
+    Symbol(char),
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Size(n) => write!(f, "no grid has {} cells", n),
+            GridError::Symbol(c) => write!(f, "invalid symbol '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// Parses the one line form, the size follows from the length. Empty cells
+/// are `.` or `0`.
+impl FromStr for Grid {
+    type Err = GridError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let count = input.chars().count();
+        let size = Size::from_cells(count).ok_or(GridError::Size(count))?;
+        let mut grid = Grid::new(size);
+        for (cell, symbol) in grid.cells.iter_mut().zip(input.chars()) {
+            *cell = match symbol {
+                '.' | '0' => 0,
+                c => match SYMBOLS
+                    .iter()
+                    .position(|&s| s as char == c.to_ascii_uppercase())
+                {
+                    Some(i) if i < size.n() => i as u8 + 1,
+                    _ => return Err(GridError::Symbol(c)),
+                },
+            };
+        }
+        Ok(grid)
+    }
+}
+
+/// A square board of one of the supported sizes.
+impl TryFrom<Sudoku> for Grid {
+    type Error = GridError;
+
+    fn try_from(sudoku: Sudoku) -> Result<Self, Self::Error> {
+        let cells = sudoku.inner().len();
+        match Size::from_cells(cells) {
+            Some(size) if sudoku.rows() == size.n() => Ok(Self {
+                size,
+                cells: sudoku.inner().to_vec(),
+            }),
+            _ => Err(GridError::Size(cells)),
+        }
+    }
+}
+
+impl From<&Grid> for Sudoku {
+    fn from(grid: &Grid) -> Self {
+        let n = grid.size.n();
+        let mut sudoku = Sudoku::new(n, n);
+        for (index, &value) in grid.cells.iter().enumerate() {
+            sudoku.set_cell(Cell::from_index(index, n), value);
+        }
+        sudoku
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+
+    use crate::{sudoku::Solution, Branching, Budget, Cell, Config, Layout, Solver, Sudoku};
+
+    use super::{Grid, GridError, Size};
+
+    /// Checks every row, column and box of a complete grid.
+    fn assert_valid(grid: &Grid) {
+        let size = grid.size();
+        for region in Layout::sized(size).regions() {
+            let mut digits: Vec<u8> = region
+                .cells
+                .iter()
+                .map(|c| grid.get(c.row, c.col))
+                .collect();
+            digits.sort_unstable();
+            assert_eq!(digits, (1..=size.n() as u8).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn grid_small() {
+        let four: Grid = "1...  ..3.  .4..  ...2".replace(' ', "").parse().unwrap();
+        assert_eq!(four.size(), Size::FOUR);
+        let solved = four.solve().unwrap();
+        assert_valid(&solved);
+        assert_eq!(four.count_solutions(2), 1);
+
+        // 6x6 with boxes of two rows and three columns.
+        let six: Grid = "....5. 4.6..3 .3.... 5.4... .126.. ....1."
+            .replace(' ', "")
+            .parse()
+            .unwrap();
+        assert_eq!(six.size(), Size::SIX);
+        assert_eq!(six.count_solutions(2), 1);
+        let solved = six.solve().unwrap();
+        assert_valid(&solved);
+        assert_eq!(solved.to_string(), "123456456123231564564231312645645312");
+        assert_eq!(six.hardest(), Some(Solver::Base));
+    }
+
+    #[test]
+    fn grid_pipeline() {
+        let six: Grid = "....5. 4.6..3 .3.... 5.4... .126.. ....1."
+            .replace(' ', "")
+            .parse()
+            .unwrap();
+        let layout = Layout::sized(six.size());
+        assert_eq!(layout.digits(), 6);
+        assert_eq!(layout.box_of(Cell::new(1, 4)), Some(1));
+        assert_eq!(layout.box_of(Cell::new(2, 0)), Some(2));
+        assert_eq!((layout.rows(), layout.cols()), (6, 6));
+        assert_eq!(layout.cells().len(), 36);
+
+        // The steps are those of the 9x9 pipeline, on the 6x6 board.
+        let solve = six.solve_steps();
+        assert!(solve.iter().all(|s| s.valid));
+        for step in solve.iter() {
+            assert_eq!(step.sudoku.inner().len(), 36);
+            assert!(step.cache.cells().iter().all(|c| !c.has(7)));
+        }
+        assert!(solve
+            .iter()
+            .any(|s| s.explain().to_string().starts_with("Naked Single")));
+
+        // Guessing picks among the digits of the grid only.
+        for &branching in &[Branching::Mrv, Branching::HiddenSingle] {
+            let config = Config {
+                branching,
+                ..Grid::new(Size::FOUR).config()
+            };
+            let solved =
+                Grid::new(Size::FOUR)
+                    .sudoku()
+                    .solve_config(config, &Budget::unlimited(), None);
+            assert!(matches!(solved, Solution::Complete(_)));
+        }
+        assert_valid(&Grid::new(Size::SIX).solve().unwrap());
+
+        assert_eq!(".".repeat(100).parse::<Grid>(), Err(GridError::Size(100)));
+    }
+
+    #[test]
+    fn grid_nine() {
+        let text =
+            "...6..8....35.4...65..217...6..............5..7138..2...7.1.6.4.1.......9....3..7";
+        let grid: Grid = text.parse().unwrap();
+        assert_eq!(grid.size(), Size::NINE);
+        let sudoku = Sudoku::from(&grid);
+        assert_eq!(sudoku, Sudoku::from(text));
+        assert_eq!(Grid::try_from(sudoku.clone()), Ok(grid.clone()));
+        assert_eq!(
+            grid.solve().unwrap().sudoku(),
+            match sudoku.solve() {
+                Solution::Complete(solved) => solved,
+                other => panic!("expected a solution, got {:?}", other),
+            }
+        );
+        assert_eq!(grid.hardest(), Some(sudoku.rate().hardest));
+
+        assert_eq!("12".parse::<Grid>(), Err(GridError::Size(2)));
+        assert_eq!(
+            "5...........4...".parse::<Grid>(),
+            Err(GridError::Symbol('5'))
+        );
+        assert_eq!(Grid::try_from(Sudoku::new(9, 6)), Err(GridError::Size(54)));
+        assert_eq!(Grid::try_from(Sudoku::new(5, 5)), Err(GridError::Size(25)));
+    }
+
+    /// A solved grid of `size` from the shifted pattern, with the cells
+    /// whose index passes `empty` cleared.
+    fn pattern(size: Size, empty: impl Fn(usize) -> bool) -> (Grid, Grid) {
+        let n = size.n();
+        let mut solved = Grid::new(size);
+        for row in 0..n {
+            let shift = row % size.box_rows * size.box_cols + row / size.box_rows;
+            for col in 0..n {
+                solved.set(row, col, ((shift + col) % n + 1) as u8);
+            }
+        }
+        let mut puzzle = solved.clone();
+        for index in (0..n * n).filter(|&i| empty(i)) {
+            puzzle.set(index / n, index % n, 0);
+        }
+        (puzzle, solved)
+    }
+
+    #[test]
+    fn grid_large() {
+        for &size in &[Size::TWELVE, Size::SIXTEEN] {
+            let (puzzle, solved) = pattern(size, |i| i % 3 == 0);
+            assert_valid(&solved);
+            let text = puzzle.to_string();
+            assert_eq!(text.parse::<Grid>(), Ok(puzzle.clone()));
+            assert_eq!(puzzle.sudoku().rows(), size.n());
+            let found = puzzle.solve().unwrap();
+            assert_valid(&found);
+            let kept = puzzle.cells().iter().zip(found.cells());
+            assert!(kept
+                .clone()
+                .all(|(&given, &digit)| given == 0 || given == digit));
+            assert!(puzzle.solve_steps().iter().all(|s| s.valid));
+        }
+        assert_valid(&Grid::new(Size::TWELVE).solve().unwrap());
+        assert_eq!(Size::from_cells(256), Some(Size::SIXTEEN));
+        let hexadoku = "G".repeat(256).parse::<Grid>().unwrap();
+        assert_eq!(hexadoku.get(0, 0), 16);
+        assert_eq!("G".repeat(144).parse::<Grid>(), Err(GridError::Symbol('G')));
+    }
+}
@@ -0,0 +1,168 @@
+//! Killer sudoku cages.
+//!
+//! A cage is a group of cells whose digits add up to a given sum, without
+//! repeating a digit. Cages are part of the [`Layout`](crate::Layout), the
+//! cells of a cage see each other.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    notation::{self, ParseError},
+    Cell, CellOptions, Sudoku,
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Cage {
+    pub sum: u8,
+    pub cells: Vec<Cell>,
+}
+
+impl Cage {
+    pub fn new(sum: u8, cells: Vec<Cell>) -> Self {
+        Self { sum, cells }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.cells.contains(&cell)
+    }
+
+    /// Whether some set of distinct digits fills the cage.
+    pub fn is_possible(&self) -> bool {
+        combinations(self.cells.len(), self.sum as usize, 0)
+            .next()
+            .is_some()
+    }
+
+    /// Whether the digits placed in the cage still leave a way to make its
+    /// sum, or make it exactly once the cage is full.
+    pub fn is_consistent(&self, sudoku: &Sudoku) -> bool {
+        let placed: usize = self.cells.iter().map(|&c| *sudoku.cell(c) as usize).sum();
+        if self.cells.iter().all(|&c| *sudoku.cell(c) != 0) {
+            placed == self.sum as usize
+        } else {
+            !self.candidates(sudoku).is_empty()
+        }
+    }
+
+    /// The digits that can go in the empty cells of the cage, given the
+    /// digits already placed in it.
+    pub fn candidates(&self, sudoku: &Sudoku) -> CellOptions {
+        let mut used = 0;
+        let mut remaining = self.sum as usize;
+        let mut empty = 0;
+        for &cell in &self.cells {
+            match *sudoku.cell(cell) {
+                0 => empty += 1,
+                value => {
+                    used |= 1 << value;
+                    remaining = remaining.saturating_sub(value as usize);
+                }
+            }
+        }
+        let mut options = CellOptions::default();
+        for mask in combinations(empty, remaining, used) {
+            for value in 1..=9 {
+                if mask & (1 << value) != 0 {
+                    options.add(value);
+                }
+            }
+        }
+        options
+    }
+}
+
+/// Sets of `count` distinct digits adding up to `sum`, as bit masks with bit
+/// `n` for digit `n`, that avoid the digits in `exclude`.
+pub(crate) fn combinations(count: usize, sum: usize, exclude: u16) -> impl Iterator<Item = u16> {
+    (0u16..512)
+        .map(|mask| mask << 1)
+        .filter(move |mask| mask & exclude == 0 && mask.count_ones() as usize == count)
+        .filter(move |mask| (1..=9).filter(|n| mask & (1 << n) != 0).sum::<usize>() == sum)
+}
+
+/// `sum=cells`, for example `15=r1c12,r2c1`.
+impl fmt::Display for Cage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.sum, notation::group(&self.cells))
+    }
+}
+
+impl FromStr for Cage {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::new(input);
+        let (sum, cells) = input.split_once('=').ok_or_else(invalid)?;
+        let sum = sum.parse().map_err(|_| invalid())?;
+        let mut out = Vec::new();
+        for group in cells.split(',') {
+            out.extend(notation::parse_group(group)?);
+        }
+        Ok(Cage::new(sum, out))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, Sudoku};
+
+    use super::{combinations, Cage};
+
+    #[test]
+    fn cage_candidates() {
+        // 3 in two cells is always 1 + 2, 17 is always 8 + 9.
+        let cage = Cage::new(3, vec![Cell::new(0, 0), Cell::new(0, 1)]);
+        assert_eq!(
+            cage.candidates(&Sudoku::default())
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let cage = Cage::new(17, vec![Cell::new(0, 0), Cell::new(1, 0)]);
+        assert_eq!(
+            cage.candidates(&Sudoku::default())
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![8, 9]
+        );
+
+        let cage = Cage::new(15, vec![Cell::new(0, 0), Cell::new(0, 1), Cell::new(1, 0)]);
+        let mut sudoku = Sudoku::default();
+        sudoku.set_cell(Cell::new(0, 0), 9);
+        // 6 left in two cells without 9: 1 + 5 or 2 + 4.
+        assert_eq!(
+            cage.candidates(&sudoku).iter().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5]
+        );
+
+        assert_eq!(combinations(9, 45, 0).count(), 1);
+        assert!(!Cage::new(2, vec![Cell::new(0, 0), Cell::new(0, 1)]).is_possible());
+
+        // With 9 placed, 6 is still open, 4 + 5 is not.
+        assert!(cage.is_consistent(&sudoku));
+        sudoku.set_cell(Cell::new(0, 1), 5);
+        sudoku.set_cell(Cell::new(1, 0), 4);
+        assert!(!cage.is_consistent(&sudoku));
+        sudoku.set_cell(Cell::new(1, 0), 1);
+        assert!(cage.is_consistent(&sudoku));
+        sudoku.set_cell(Cell::new(1, 0), 0);
+        sudoku.set_cell(Cell::new(0, 1), 6);
+        assert!(!cage.is_consistent(&sudoku));
+    }
+
+    #[test]
+    fn cage_notation() {
+        let cage: Cage = "15=r1c12,r2c1".parse().unwrap();
+        assert_eq!(cage.sum, 15);
+        assert_eq!(
+            cage.cells,
+            vec![Cell::new(0, 0), Cell::new(0, 1), Cell::new(1, 0)]
+        );
+        assert_eq!(cage.to_string().parse::<Cage>(), Ok(cage));
+        assert_eq!("10=r1c12".parse::<Cage>().unwrap().to_string(), "10=r1c12");
+        assert!("r1c12".parse::<Cage>().is_err());
+        assert!("x=r1c12".parse::<Cage>().is_err());
+    }
+}
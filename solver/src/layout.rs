@@ -0,0 +1,795 @@
+//! The regions of a puzzle and the peer relation between its cells.
+//!
+//! Every region must contain each digit exactly once, two cells are peers
+//! when they share a region. Standard sudoku is the layout with nine rows,
+//! nine columns and nine boxes. Jigsaw sudoku replaces the boxes with nine
+//! irregular pieces, which keep the `Domain::Sqr` domain so box techniques
+//! apply to them unchanged. Killer cages add groups of cells that don't
+//! repeat a digit and must add up to a sum. The anti-knight and anti-king
+//! constraints make cells a chess knight's or king's move apart peers.
+//! Thermometers, arrows and sandwich clues restrict digits beyond the peer
+//! relation, see [`crate::lines`], as do the Kropki and XV marks and the
+//! greater-than signs between adjacent cells of [`crate::edges`]. Odd and
+//! even cells of [`crate::parity`] narrow the starting options instead.
+//!
+//! The board spans the rows and columns the regions reach. Cells outside
+//! every region aren't part of the puzzle and stay empty.
+
+use std::{fmt, rc::Rc};
+
+use crate::{
+    edges::{self, Edge, Inequality, Mark},
+    grid::Size,
+    killer::Cage,
+    lines::{Arrow, Sandwich, Thermo},
+    parity::Parity,
+    util::Domain,
+    Cell, Sudoku,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub domain: Domain,
+    pub cells: Vec<Cell>,
+}
+
+impl Region {
+    pub fn new(domain: Domain, cells: Vec<Cell>) -> Self {
+        Self { domain, cells }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.cells.contains(&cell)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    regions: Vec<Region>,
+    /// The size of the board, the extent of the regions.
+    rows: usize,
+    cols: usize,
+    /// Cells in at least one region, in reading order.
+    cells: Vec<Cell>,
+    /// Indices of the regions each cell belongs to.
+    member: Vec<Vec<usize>>,
+    /// Peers of each cell, in index order.
+    peers: Vec<Vec<Cell>>,
+    /// Ordered pairs of regions that share at least two cells.
+    overlaps: Vec<(usize, usize)>,
+    cages: Vec<Cage>,
+    /// Index of the cage of each cell.
+    cage_of: Vec<Option<usize>>,
+    anti_knight: bool,
+    anti_king: bool,
+    thermos: Vec<Thermo>,
+    arrows: Vec<Arrow>,
+    sandwiches: Vec<Sandwich>,
+    edges: Vec<Edge>,
+    /// Marks whose relation is ruled out between unmarked neighbours.
+    negative: Vec<Mark>,
+    inequalities: Vec<Inequality>,
+    /// The parity of each shaded cell.
+    parity: Vec<Option<Parity>>,
+    /// Digits go from 1 up to this, 9 unless the grid has another size.
+    digits: u8,
+}
+
+impl Layout {
+    pub fn new(regions: Vec<Region>) -> Self {
+        let cells = || regions.iter().flat_map(|r| r.cells.iter());
+        let rows = cells().map(|c| c.row + 1).max().unwrap_or_default();
+        let cols = cells().map(|c| c.col + 1).max().unwrap_or_default();
+        let mut layout = Self {
+            regions,
+            rows,
+            cols,
+            cells: Vec::new(),
+            member: Vec::new(),
+            peers: Vec::new(),
+            overlaps: Vec::new(),
+            cages: Vec::new(),
+            cage_of: vec![None; rows * cols],
+            anti_knight: false,
+            anti_king: false,
+            thermos: Vec::new(),
+            arrows: Vec::new(),
+            sandwiches: Vec::new(),
+            edges: Vec::new(),
+            negative: Vec::new(),
+            inequalities: Vec::new(),
+            parity: vec![None; rows * cols],
+            digits: 9,
+        };
+        layout.index();
+        layout
+    }
+
+    /// Recomputes the regions of every cell, the overlapping regions and the
+    /// peers.
+    fn index(&mut self) {
+        let mut member = vec![Vec::new(); self.rows * self.cols];
+        for (i, region) in self.regions.iter().enumerate() {
+            for cell in &region.cells {
+                member[cell.index(self.cols)].push(i);
+            }
+        }
+        self.member = member;
+        self.cells = (0..self.member.len())
+            .filter(|&i| !self.member[i].is_empty())
+            .map(|i| Cell::from_index(i, self.cols))
+            .collect();
+        self.overlaps.clear();
+        for (a, first) in self.regions.iter().enumerate() {
+            for (b, second) in self.regions.iter().enumerate() {
+                let shared = first.cells.iter().filter(|&&c| second.contains(c));
+                if a != b && shared.count() >= 2 {
+                    self.overlaps.push((a, b));
+                }
+            }
+        }
+        self.link();
+    }
+
+    /// Recomputes the peers of every cell from the regions, cages,
+    /// thermometers and chess constraints.
+    fn link(&mut self) {
+        let cols = self.cols;
+        let shares = |a: usize, b: usize| {
+            let (x, y) = (Cell::from_index(a, cols), Cell::from_index(b, cols));
+            let moved = (x.row.abs_diff(y.row), x.col.abs_diff(y.col));
+            self.member[a].iter().any(|r| self.member[b].contains(r))
+                || (self.cage_of[a].is_some() && self.cage_of[a] == self.cage_of[b])
+                || (self.anti_knight && matches!(moved, (1, 2) | (2, 1)))
+                || (self.anti_king && moved.0 <= 1 && moved.1 <= 1)
+                || self.thermos.iter().any(|t| t.contains(x) && t.contains(y))
+        };
+        let count = self.rows * self.cols;
+        let peers = (0..count)
+            .map(|a| {
+                (0..count)
+                    .filter(|&b| a != b && shares(a, b))
+                    .map(|b| Cell::from_index(b, cols))
+                    .collect()
+            })
+            .collect();
+        self.peers = peers;
+    }
+
+    /// Adds killer cages. Cages can't overlap and their sum must be
+    /// reachable with distinct digits.
+    pub fn with_cages(mut self, cages: Vec<Cage>) -> Result<Self, LayoutError> {
+        for (i, cage) in cages.iter().enumerate() {
+            if cage.cells.is_empty() || cage.cells.len() > 9 || !cage.is_possible() {
+                return Err(LayoutError::CageSum(i));
+            }
+            for cell in &cage.cells {
+                let at = cell.index(self.cols);
+                if self.cage_of[at].is_some() {
+                    return Err(LayoutError::CageOverlap(i));
+                }
+                self.cage_of[at] = Some(i);
+            }
+        }
+        self.cages = cages;
+        self.link();
+        Ok(self)
+    }
+
+    /// Equal digits may not be a knight's move apart.
+    pub fn with_anti_knight(mut self) -> Self {
+        self.anti_knight = true;
+        self.link();
+        self
+    }
+
+    /// Equal digits may not be a king's move apart, diagonally adjacent
+    /// cells become peers.
+    pub fn with_anti_king(mut self) -> Self {
+        self.anti_king = true;
+        self.link();
+        self
+    }
+
+    /// Adds thermometers, the cells of a thermometer see each other.
+    pub fn with_thermos(mut self, thermos: Vec<Thermo>) -> Result<Self, LayoutError> {
+        if let Some(i) = thermos.iter().position(|t| !t.is_valid()) {
+            return Err(LayoutError::Thermo(i));
+        }
+        self.thermos = thermos;
+        self.link();
+        Ok(self)
+    }
+
+    pub fn with_arrows(mut self, arrows: Vec<Arrow>) -> Result<Self, LayoutError> {
+        if let Some(i) = arrows.iter().position(|a| !a.is_valid()) {
+            return Err(LayoutError::Arrow(i));
+        }
+        self.arrows = arrows;
+        Ok(self)
+    }
+
+    pub fn with_sandwiches(mut self, sandwiches: Vec<Sandwich>) -> Result<Self, LayoutError> {
+        if let Some(i) = sandwiches.iter().position(|s| !s.is_valid()) {
+            return Err(LayoutError::Sandwich(i));
+        }
+        self.sandwiches = sandwiches;
+        Ok(self)
+    }
+
+    /// Adds Kropki and XV marks between orthogonally adjacent cells, with
+    /// the `negative` marks ruled out between cells without a mark.
+    pub fn with_edges(
+        mut self,
+        edges: Vec<Edge>,
+        negative: Vec<Mark>,
+    ) -> Result<Self, LayoutError> {
+        for (i, edge) in edges.iter().enumerate() {
+            if !edge.is_valid() || edges[..i].iter().any(|e| e.joins(edge.a, edge.b)) {
+                return Err(LayoutError::Edge(i));
+            }
+        }
+        self.edges = edges;
+        self.negative = negative;
+        Ok(self)
+    }
+
+    /// Adds greater-than signs between orthogonally adjacent cells.
+    pub fn with_inequalities(mut self, inequalities: Vec<Inequality>) -> Result<Self, LayoutError> {
+        for (i, sign) in inequalities.iter().enumerate() {
+            let repeated = inequalities[..i]
+                .iter()
+                .any(|s| s.joins(sign.greater, sign.less));
+            if !sign.is_valid() || repeated {
+                return Err(LayoutError::Inequality(i));
+            }
+        }
+        self.inequalities = inequalities;
+        Ok(self)
+    }
+
+    /// Restricts `cells` to odd or even digits. A cell can't have both.
+    pub fn with_parity(mut self, parity: Parity, cells: &[Cell]) -> Result<Self, LayoutError> {
+        for cell in cells {
+            let at = cell.index(self.cols);
+            match self.parity[at] {
+                Some(other) if other != parity => return Err(LayoutError::Parity(*cell)),
+                _ => self.parity[at] = Some(parity),
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn is_anti_knight(&self) -> bool {
+        self.anti_knight
+    }
+
+    pub fn is_anti_king(&self) -> bool {
+        self.anti_king
+    }
+
+    /// Rows, columns and boxes.
+    pub fn standard() -> Self {
+        let mut regions = Vec::with_capacity(27);
+        for n in 0..9 {
+            for &domain in &[Domain::Row(n), Domain::Col(n), Domain::Sqr(n)] {
+                regions.push(Region::new(
+                    domain,
+                    (0..9).map(|i| domain.cell(i)).collect(),
+                ));
+            }
+        }
+        Self::new(regions)
+    }
+
+    /// Sudoku-X, the standard layout with both main diagonals as extra regions.
+    pub fn diagonal() -> Self {
+        Self::standard().with_diagonals()
+    }
+
+    /// Adds both main diagonals as extra regions.
+    pub fn with_diagonals(mut self) -> Self {
+        for &domain in &[Domain::Diag(0), Domain::Diag(1)] {
+            self.regions.push(Region::new(
+                domain,
+                (0..9).map(|i| domain.cell(i)).collect(),
+            ));
+        }
+        self.index();
+        self
+    }
+
+    /// The rows, columns and boxes of a grid of `size`, whose cells take the
+    /// digits up to the size, see [`crate::grid`].
+    pub fn sized(size: Size) -> Self {
+        let n = size.n();
+        let mut regions = Vec::with_capacity(3 * n);
+        for i in 0..n {
+            let (top, left) = (
+                i / size.box_rows * size.box_rows,
+                i % size.box_rows * size.box_cols,
+            );
+            regions.push(Region::new(
+                Domain::Row(i),
+                (0..n).map(|j| Cell::new(i, j)).collect(),
+            ));
+            regions.push(Region::new(
+                Domain::Col(i),
+                (0..n).map(|j| Cell::new(j, i)).collect(),
+            ));
+            regions.push(Region::new(
+                Domain::Sqr(i),
+                (0..n)
+                    .map(|j| Cell::new(top + j / size.box_cols, left + j % size.box_cols))
+                    .collect(),
+            ));
+        }
+        let mut layout = Self::new(regions);
+        layout.digits = n as u8;
+        layout
+    }
+
+    /// Rows, columns and the pieces of a jigsaw `map`.
+    ///
+    /// The map has 81 symbols in reading order, cells with the same symbol
+    /// form a piece. There must be nine pieces of nine orthogonally connected
+    /// cells each. Pieces are numbered in order of first appearance.
+    pub fn jigsaw(map: &str) -> Result<Self, LayoutError> {
+        let symbols: Vec<char> = map.trim().chars().collect();
+        if symbols.len() != 81 {
+            return Err(LayoutError::Length(symbols.len()));
+        }
+        let mut order: Vec<char> = Vec::with_capacity(9);
+        for &symbol in &symbols {
+            if !order.contains(&symbol) {
+                order.push(symbol);
+            }
+        }
+        if order.len() != 9 {
+            return Err(LayoutError::Pieces(order.len()));
+        }
+
+        let mut pieces = Vec::with_capacity(9);
+        for &symbol in &order {
+            let cells: Vec<Cell> = (0..81)
+                .filter(|&i| symbols[i] == symbol)
+                .map(|i| Cell::from_index(i, 9))
+                .collect();
+            if cells.len() != 9 {
+                return Err(LayoutError::Size(symbol, cells.len()));
+            }
+            if !is_connected(&cells) {
+                return Err(LayoutError::Disconnected(symbol));
+            }
+            pieces.push(cells);
+        }
+
+        let mut regions = Vec::with_capacity(27);
+        for (n, piece) in pieces.into_iter().enumerate() {
+            for &domain in &[Domain::Row(n), Domain::Col(n)] {
+                regions.push(Region::new(
+                    domain,
+                    (0..9).map(|i| domain.cell(i)).collect(),
+                ));
+            }
+            regions.push(Region::new(Domain::Sqr(n), piece));
+        }
+        Ok(Self::new(regions))
+    }
+
+    /// The standard layout, built once per thread.
+    pub fn shared() -> Rc<Self> {
+        thread_local! {
+            static STANDARD: Rc<Layout> = Rc::new(Layout::standard());
+        }
+        STANDARD.with(Rc::clone)
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The cells of the puzzle, those in at least one region.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// An empty board of the size of the layout.
+    pub fn empty(&self) -> Sudoku {
+        Sudoku::new(self.rows, self.cols)
+    }
+
+    /// The largest digit.
+    pub fn digits(&self) -> u8 {
+        self.digits
+    }
+
+    pub fn region(&self, domain: Domain) -> Option<&Region> {
+        self.regions.iter().find(|r| r.domain == domain)
+    }
+
+    /// Whether the region for `domain` contains `cell`.
+    pub fn in_region(&self, domain: Domain, cell: Cell) -> bool {
+        matches!(self.region(domain), Some(region) if region.contains(cell))
+    }
+
+    /// The regions that contain `cell`.
+    pub fn regions_of(&self, cell: Cell) -> impl Iterator<Item = &Region> + '_ {
+        self.member[cell.index(self.cols)]
+            .iter()
+            .map(move |&i| &self.regions[i])
+    }
+
+    /// Pairs of regions that share two or more cells, such as a box and a row.
+    pub fn overlaps(&self) -> impl Iterator<Item = (&Region, &Region)> + '_ {
+        self.overlaps
+            .iter()
+            .map(move |&(a, b)| (&self.regions[a], &self.regions[b]))
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// The killer cage that contains `cell`.
+    pub fn cage_of(&self, cell: Cell) -> Option<&Cage> {
+        self.cage_of[cell.index(self.cols)].map(|i| &self.cages[i])
+    }
+
+    pub fn thermos(&self) -> &[Thermo] {
+        &self.thermos
+    }
+
+    pub fn arrows(&self) -> &[Arrow] {
+        &self.arrows
+    }
+
+    pub fn sandwiches(&self) -> &[Sandwich] {
+        &self.sandwiches
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn negative(&self) -> &[Mark] {
+        &self.negative
+    }
+
+    pub fn inequalities(&self) -> &[Inequality] {
+        &self.inequalities
+    }
+
+    /// The greater-than sign between `a` and `b`.
+    pub fn inequality(&self, a: Cell, b: Cell) -> Option<&Inequality> {
+        self.inequalities.iter().find(|s| s.joins(a, b))
+    }
+
+    /// Whether `cell` is shaded odd or even.
+    pub fn parity_of(&self, cell: Cell) -> Option<Parity> {
+        self.parity[cell.index(self.cols)]
+    }
+
+    /// The mark between `a` and `b`.
+    pub fn edge(&self, a: Cell, b: Cell) -> Option<&Edge> {
+        self.edges.iter().find(|e| e.joins(a, b))
+    }
+
+    /// Whether placing `value` at `cell` keeps the thermometers, arrows,
+    /// sandwiches, edge marks and greater-than signs satisfiable, judged by
+    /// the digits already placed.
+    pub fn allows(&self, sudoku: &Sudoku, cell: Cell, value: u8) -> bool {
+        edges::allows(&self.edges, &self.negative, sudoku, cell, value)
+            && self
+                .inequalities
+                .iter()
+                .all(|s| s.allows(sudoku, cell, value))
+            && self.thermos.iter().all(|t| t.allows(sudoku, cell, value))
+            && self.arrows.iter().all(|a| a.allows(sudoku, cell, value))
+            && self
+                .sandwiches
+                .iter()
+                .all(|s| s.allows(sudoku, cell, value))
+    }
+
+    /// Whether the placed digits keep to the constraints beyond the peers,
+    /// the sums of killer cages and everything [`Layout::allows`] checks.
+    pub fn is_consistent(&self, sudoku: &Sudoku) -> bool {
+        self.cages.iter().all(|cage| cage.is_consistent(sudoku))
+            && self.cells.iter().all(|&cell| match *sudoku.cell(cell) {
+                0 => true,
+                value => self.allows(sudoku, cell, value),
+            })
+    }
+
+    /// The box or jigsaw piece that contains `cell`.
+    pub fn box_of(&self, cell: Cell) -> Option<usize> {
+        self.regions_of(cell).find_map(|r| match r.domain {
+            Domain::Sqr(n) => Some(n),
+            _ => None,
+        })
+    }
+
+    pub fn peers(&self, cell: Cell) -> &[Cell] {
+        &self.peers[cell.index(self.cols)]
+    }
+
+    /// Whether `a` and `b` can't hold the same digit.
+    pub fn sees(&self, a: Cell, b: Cell) -> bool {
+        self.peers[a.index(self.cols)].contains(&b)
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// Whether `cells` form a single orthogonally connected group.
+fn is_connected(cells: &[Cell]) -> bool {
+    let mut reached = vec![cells[0]];
+    let mut next = 0;
+    while next < reached.len() {
+        let cell = reached[next];
+        next += 1;
+        for &other in cells {
+            let adjacent = (cell.row == other.row && cell.col.abs_diff(other.col) == 1)
+                || (cell.col == other.col && cell.row.abs_diff(other.row) == 1);
+            if adjacent && !reached.contains(&other) {
+                reached.push(other);
+            }
+        }
+    }
+    reached.len() == cells.len()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The map does not have 81 cells.
+    Length(usize),
+    /// The map does not have nine distinct pieces.
+    Pieces(usize),
+    /// A piece does not have nine cells.
+    Size(char, usize),
+    /// A piece is split into separate parts.
+    Disconnected(char),
+    /// Cage `n` shares a cell with an earlier cage.
+    CageOverlap(usize),
+    /// The sum of cage `n` can't be made with distinct digits.
+    CageSum(usize),
+    /// Thermometer `n` isn't a path of two to nine cells.
+    Thermo(usize),
+    /// Arrow `n` isn't a path leading away from its circle.
+    Arrow(usize),
+    /// Sandwich `n` isn't on a row or column, or has an impossible sum.
+    Sandwich(usize),
+    /// Edge `n` doesn't join adjacent cells, or repeats an earlier edge.
+    Edge(usize),
+    /// Inequality `n` doesn't join adjacent cells, or repeats an earlier one.
+    Inequality(usize),
+    /// The cell is shaded both odd and even.
+    Parity(Cell),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Length(n) => write!(f, "region map has {} cells, expected 81", n),
+            LayoutError::Pieces(n) => write!(f, "region map has {} pieces, expected 9", n),
+            LayoutError::Size(symbol, n) => {
+                write!(f, "piece '{}' has {} cells, expected 9", symbol, n)
+            }
+            LayoutError::Disconnected(symbol) => write!(f, "piece '{}' is not connected", symbol),
+            LayoutError::CageOverlap(n) => write!(f, "cage {} overlaps another cage", n + 1),
+            LayoutError::CageSum(n) => write!(f, "cage {} has an impossible sum", n + 1),
+            LayoutError::Thermo(n) => write!(f, "thermometer {} is not a valid path", n + 1),
+            LayoutError::Arrow(n) => write!(f, "arrow {} is not a valid path", n + 1),
+            LayoutError::Sandwich(n) => write!(f, "sandwich {} is not a valid clue", n + 1),
+            LayoutError::Edge(n) => write!(f, "edge {} doesn't join two neighbours", n + 1),
+            LayoutError::Inequality(n) => {
+                write!(f, "inequality {} doesn't join two neighbours", n + 1)
+            }
+            LayoutError::Parity(n) => {
+                write!(f, "cell {} is both odd and even", n)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        killer::Cage, lines::Thermo, util::Domain, Cell, ModMarking, Solver, StateMod, Sudoku,
+    };
+
+    use super::{Layout, LayoutError};
+
+    static JIGSAW: &str = concat!(
+        "111223333",
+        "111222333",
+        "114222236",
+        "144455536",
+        "445555566",
+        "444566669",
+        "777888699",
+        "777888999",
+        "777888999",
+    );
+
+    #[test]
+    fn layout_standard() {
+        let layout = Layout::standard();
+        assert_eq!(layout.regions().len(), 27);
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            assert_eq!(layout.peers(cell).len(), 20);
+            assert_eq!(layout.regions_of(cell).count(), 3);
+            for other in (0..81).map(|i| Cell::from_index(i, 9)) {
+                assert_eq!(layout.sees(cell, other), cell != other && cell.sees(other));
+            }
+        }
+        // Every box overlaps three rows and three columns, in both directions.
+        assert_eq!(layout.overlaps().count(), 108);
+        assert_eq!(
+            layout.region(Domain::Sqr(4)).unwrap().cells[0],
+            Cell::new(3, 3)
+        );
+    }
+
+    #[test]
+    fn layout_diagonal() {
+        let layout = Layout::diagonal();
+        assert_eq!(layout.regions().len(), 29);
+        // The center lies on both diagonals and sees every diagonal cell.
+        assert_eq!(layout.peers(Cell::new(4, 4)).len(), 32);
+        assert!(layout.sees(Cell::new(0, 0), Cell::new(8, 8)));
+        assert!(layout.sees(Cell::new(0, 8), Cell::new(8, 0)));
+        assert!(!layout.sees(Cell::new(0, 1), Cell::new(8, 7)));
+        // Both diagonals cross three boxes.
+        assert_eq!(layout.overlaps().count(), 108 + 12);
+
+        // Constraints added before the diagonals are kept.
+        let cage = Cage::new(3, vec![Cell::new(0, 1), Cell::new(1, 3)]);
+        let layout = Layout::standard()
+            .with_cages(vec![cage])
+            .unwrap()
+            .with_anti_king()
+            .with_diagonals();
+        assert_eq!(layout.regions().len(), 29);
+        assert_eq!(layout.cages().len(), 1);
+        assert!(layout.is_anti_king());
+        assert!(layout.sees(Cell::new(0, 1), Cell::new(1, 3)));
+        assert!(layout.sees(Cell::new(0, 0), Cell::new(8, 8)));
+    }
+
+    #[test]
+    fn layout_jigsaw() {
+        let layout = Layout::jigsaw(JIGSAW).unwrap();
+        assert_eq!(layout.regions().len(), 27);
+        assert_eq!(layout.box_of(Cell::new(0, 8)), Some(2));
+        assert_eq!(layout.box_of(Cell::new(2, 2)), Some(3));
+
+        // Marked pieces resolve to the cells of the jigsaw, not the boxes.
+        let mut change = StateMod::from(Solver::Elim);
+        change.push_mark(ModMarking::Domain(Domain::Sqr(0)));
+        change.push_mark(ModMarking::Domain(Domain::Sqr(3)));
+        let marked = |layout| {
+            change
+                .marked_domains(Cell::new(2, 2), layout)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(marked(&layout), vec![Domain::Sqr(3)]);
+        assert_eq!(marked(&Layout::standard()), vec![Domain::Sqr(0)]);
+        assert!(layout.sees(Cell::new(0, 0), Cell::new(1, 2)));
+        assert!(!layout.sees(Cell::new(0, 0), Cell::new(2, 2)));
+        for index in 0..81 {
+            assert_eq!(layout.regions_of(Cell::from_index(index, 9)).count(), 3);
+        }
+
+        let boxes: String = (0..81)
+            .map(|i| (b'a' + Cell::from_index(i, 9).sqr() as u8) as char)
+            .collect();
+        let standard = Layout::jigsaw(&boxes).unwrap();
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            assert_eq!(standard.peers(cell), Layout::standard().peers(cell));
+        }
+    }
+
+    #[test]
+    fn layout_jigsaw_invalid() {
+        assert_eq!(Layout::jigsaw("1234").unwrap_err(), LayoutError::Length(4));
+        let eight = JIGSAW.replace('9', "8");
+        assert_eq!(Layout::jigsaw(&eight).unwrap_err(), LayoutError::Pieces(8));
+        let mut sizes = JIGSAW.to_string();
+        sizes.replace_range(0..1, "2");
+        assert_eq!(
+            Layout::jigsaw(&sizes).unwrap_err(),
+            LayoutError::Size('2', 10)
+        );
+        // Swapping two cells keeps the sizes but splits both pieces.
+        let mut split = JIGSAW.to_string();
+        split.replace_range(0..1, "9");
+        split.replace_range(80..81, "1");
+        assert_eq!(
+            Layout::jigsaw(&split).unwrap_err(),
+            LayoutError::Disconnected('9')
+        );
+    }
+
+    #[test]
+    fn layout_cages() {
+        let cage = Cage::new(10, vec![Cell::new(0, 0), Cell::new(1, 1), Cell::new(4, 4)]);
+        let layout = Layout::standard().with_cages(vec![cage.clone()]).unwrap();
+        assert!(layout.sees(Cell::new(0, 0), Cell::new(4, 4)));
+        assert_eq!(layout.peers(Cell::new(4, 4)).len(), 22);
+        assert_eq!(layout.cage_of(Cell::new(1, 1)), Some(&cage));
+        assert_eq!(layout.cage_of(Cell::new(1, 2)), None);
+
+        let overlap = Cage::new(3, vec![Cell::new(4, 4), Cell::new(4, 5)]);
+        assert_eq!(
+            Layout::standard()
+                .with_cages(vec![cage, overlap])
+                .unwrap_err(),
+            LayoutError::CageOverlap(1)
+        );
+        let impossible = Cage::new(46, (0..9).map(|i| Cell::new(8, i)).collect());
+        assert_eq!(
+            Layout::standard().with_cages(vec![impossible]).unwrap_err(),
+            LayoutError::CageSum(0)
+        );
+    }
+
+    #[test]
+    fn layout_consistent() {
+        let thermo = Thermo::new(vec![Cell::new(0, 0), Cell::new(0, 1)]);
+        let cage = Cage::new(4, vec![Cell::new(8, 0), Cell::new(8, 1)]);
+        let layout = Layout::standard()
+            .with_thermos(vec![thermo])
+            .unwrap()
+            .with_cages(vec![cage])
+            .unwrap();
+        let mut sudoku = Sudoku::default();
+        sudoku.set_cell(Cell::new(0, 0), 5);
+        assert!(layout.is_consistent(&sudoku));
+        sudoku.set_cell(Cell::new(0, 1), 3);
+        assert!(!layout.is_consistent(&sudoku));
+        sudoku.set_cell(Cell::new(0, 1), 6);
+        sudoku.set_cell(Cell::new(8, 0), 2);
+        assert!(!layout.is_consistent(&sudoku));
+        sudoku.set_cell(Cell::new(8, 0), 3);
+        assert!(layout.is_consistent(&sudoku));
+    }
+
+    #[test]
+    fn layout_chess() {
+        let center = Cell::new(4, 4);
+        let knight = Layout::standard().with_anti_knight();
+        // All eight knight moves from the center leave its box.
+        assert_eq!(knight.peers(center).len(), 28);
+        assert!(knight.sees(center, Cell::new(2, 3)));
+        assert!(knight.sees(Cell::new(0, 0), Cell::new(1, 2)));
+        assert!(!knight.sees(center, Cell::new(2, 2)));
+
+        let king = Layout::standard().with_anti_king();
+        // The neighbours of the center are all in its box already.
+        assert_eq!(king.peers(center).len(), 20);
+        assert!(king.sees(Cell::new(2, 2), Cell::new(3, 3)));
+        assert!(!Layout::standard().sees(Cell::new(2, 2), Cell::new(3, 3)));
+
+        let both = Layout::standard().with_anti_king().with_anti_knight();
+        assert!(both.is_anti_king() && both.is_anti_knight());
+        assert_eq!(both.peers(Cell::new(2, 2)).len(), 20 + 3 + 6);
+    }
+}
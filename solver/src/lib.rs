@@ -1,7 +1,22 @@
+pub mod budget;
+pub mod canonical;
+pub mod edges;
+pub mod explain;
+pub mod generate;
+pub mod grid;
+pub mod killer;
+pub mod layout;
+pub mod lines;
+pub mod multi;
+pub mod notation;
 pub mod options;
 pub mod output;
+pub mod parity;
+pub mod puzzle;
+pub mod rating;
 pub mod solvers;
 pub mod sudoku;
+pub mod transform;
 pub mod util;
 
 use std::rc::Rc;
@@ -11,10 +26,22 @@ use util::Domain;
 
 #[doc(inline)]
 pub use {
+    budget::Budget,
+    edges::{Edge, Inequality, Mark},
+    generate::{Generator, Symmetry},
+    grid::{Grid, Size},
+    killer::Cage,
+    layout::{Layout, LayoutError, Region},
+    lines::{Arrow, Sandwich, Thermo},
+    multi::MultiGrid,
     options::{CellOptions, Options},
-    output::{Solve, SolveStep},
+    output::{CompactSolve, Solve, SolveStep, StepIter},
+    parity::Parity,
+    puzzle::Puzzle,
+    rating::{Grade, Rating, SeRating},
     solvers::Solver,
     sudoku::Sudoku,
+    transform::Transform,
     util::Cell,
 };
 
@@ -193,6 +220,23 @@ impl StateMod {
         self.marks.push(mark);
     }
 
+    pub fn marks(&self) -> &[ModMarking] {
+        &self.marks
+    }
+
+    /// The regions marked by this change that contain `cell`, looked up in
+    /// `layout` so jigsaw pieces resolve to their own cells.
+    pub fn marked_domains<'a>(
+        &'a self,
+        cell: Cell,
+        layout: &'a Layout,
+    ) -> impl Iterator<Item = Domain> + 'a {
+        self.marks.iter().filter_map(move |m| match m {
+            ModMarking::Domain(d) if layout.in_region(*d, cell) => Some(*d),
+            _ => None,
+        })
+    }
+
     pub fn is_target(&self, cell: Cell) -> bool {
         self.target.iter().any(|c| c.cell == cell)
     }
@@ -280,6 +324,12 @@ pub struct Config {
     pub base: Solver,
     pub solvers: Vec<Solver>,
     pub fallback: Option<Solver>,
+    /// Randomizes the guessing order, guesses are made in row-major cell
+    /// order and ascending digit order when `None`.
+    pub shuffle: Option<Shuffle>,
+    pub branching: Branching,
+    /// The regions every technique works on.
+    pub layout: Rc<Layout>,
 }
 
 impl Default for Config {
@@ -294,6 +344,80 @@ impl Default for Config {
                 Solver::XYWing,
             ],
             fallback: Some(Solver::BackTrace),
+            shuffle: None,
+            branching: Branching::default(),
+            layout: Layout::shared(),
+        }
+    }
+}
+
+impl Config {
+    /// The default configuration on `layout`. The techniques for its
+    /// constraints go after [`Solver::Elim`], only those that are present.
+    pub fn for_layout(layout: Rc<Layout>) -> Self {
+        let cages = !layout.cages().is_empty();
+        let variants = [
+            (Solver::Cage, cages),
+            (Solver::Innies, cages),
+            (Solver::Thermo, !layout.thermos().is_empty()),
+            (Solver::Arrow, !layout.arrows().is_empty()),
+            (Solver::Sandwich, !layout.sandwiches().is_empty()),
+            (
+                Solver::Edges,
+                !layout.edges().is_empty() || !layout.negative().is_empty(),
+            ),
+            (Solver::Inequality, !layout.inequalities().is_empty()),
+        ];
+        let mut config = Config {
+            layout,
+            ..Default::default()
+        };
+        let at = config
+            .solvers
+            .iter()
+            .position(|&s| s == Solver::Elim)
+            .map_or(0, |i| i + 1);
+        config.solvers.splice(
+            at..at,
+            variants
+                .iter()
+                .filter(|(_, present)| *present)
+                .map(|&(solver, _)| solver),
+        );
+        config
+    }
+}
+
+/// How [`Solver::BackTrace`] picks what to guess.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Branching {
+    /// The cell with the fewest candidates (minimum remaining values).
+    #[default]
+    Mrv,
+    /// The digit with the fewest places left in a row, column or box.
+    HiddenSingle,
+    /// Fewest candidates, ties go to the cell with the most empty peers.
+    Degree,
+    /// The first empty cell, as a baseline.
+    First,
+}
+
+/// Seeded random guessing order for [`Solver::BackTrace`].
+#[derive(Debug, Copy, Clone)]
+pub struct Shuffle {
+    pub seed: u64,
+    /// Break ties between cells with the fewest candidates at random.
+    pub cells: bool,
+    /// Try the alternatives of a guess in random order.
+    pub digits: bool,
+}
+
+impl Shuffle {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            cells: true,
+            digits: true,
         }
     }
 }
@@ -307,6 +431,12 @@ pub struct State {
 }
 
 impl State {
+    /// The candidates of `cell` under the layout of the puzzle.
+    pub fn candidates(&mut self, cell: Cell) -> CellOptions {
+        self.options
+            .options_in(cell, &self.sudoku, &self.config.layout)
+    }
+
     pub fn update(&mut self, cell: Cell, value: u8) {
         self.sudoku.set_cell(cell, value);
     }
@@ -0,0 +1,301 @@
+//! Thermometers, arrows and sandwich clues.
+//!
+//! Like killer cages these are part of the [`Layout`](crate::Layout). Each
+//! one checks a digit against the digits already placed, which keeps guesses
+//! honest, and has a technique that prunes candidates:
+//!
+//! - thermometer: `thermo:r1c1,r2c2,r2c3`, digits strictly increase from the
+//!   bulb at the first cell
+//! - arrow: `arrow:r5c5=r4c4,r3c3`, the circle equals the sum of the cells
+//!   along the arrow
+//! - sandwich: `sandwich:r3=15`, the digits between the 1 and the 9 of the row
+//!   or column add up to the clue
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    notation::{self, ParseError},
+    util::Domain,
+    Cell, Sudoku,
+};
+
+/// Whether every cell is a king's move away from the one before it.
+fn is_path(cells: &[Cell]) -> bool {
+    cells.windows(2).all(|pair| {
+        let (a, b) = (pair[0], pair[1]);
+        a != b && a.row.abs_diff(b.row) <= 1 && a.col.abs_diff(b.col) <= 1
+    })
+}
+
+fn has_repeats(cells: &[Cell]) -> bool {
+    cells
+        .iter()
+        .enumerate()
+        .any(|(i, c)| cells[..i].contains(c))
+}
+
+/// Parses comma separated groups, keeping the order of the cells.
+fn parse_path(input: &str) -> Result<Vec<Cell>, ParseError> {
+    let mut cells = Vec::new();
+    for group in input.split(',') {
+        cells.extend(notation::parse_group(group)?);
+    }
+    Ok(cells)
+}
+
+/// The digit `sudoku` would have at `cell` after placing `value` at `at`.
+fn digit(sudoku: &Sudoku, cell: Cell, at: Cell, value: u8) -> u8 {
+    if cell == at {
+        value
+    } else {
+        *sudoku.cell(cell)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Thermo {
+    /// The bulb first.
+    pub cells: Vec<Cell>,
+}
+
+impl Thermo {
+    pub fn new(cells: Vec<Cell>) -> Self {
+        Self { cells }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.cells.contains(&cell)
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        (2..=9).contains(&self.cells.len()) && is_path(&self.cells) && !has_repeats(&self.cells)
+    }
+
+    /// Whether `value` fits at `cell` given the digits placed on the
+    /// thermometer.
+    pub fn allows(&self, sudoku: &Sudoku, cell: Cell, value: u8) -> bool {
+        let i = match self.cells.iter().position(|&c| c == cell) {
+            Some(i) => i,
+            None => return true,
+        };
+        let value = value as usize;
+        if value <= i || value + (self.cells.len() - 1 - i) > 9 {
+            return false;
+        }
+        self.cells
+            .iter()
+            .enumerate()
+            .all(|(j, &other)| match *sudoku.cell(other) as usize {
+                0 => true,
+                _ if j == i => true,
+                placed if j < i => placed + (i - j) <= value,
+                placed => value + (j - i) <= placed,
+            })
+    }
+}
+
+impl fmt::Display for Thermo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "thermo:{}", notation::group(&self.cells))
+    }
+}
+
+impl FromStr for Thermo {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let cells = input
+            .strip_prefix("thermo:")
+            .ok_or_else(|| ParseError::new(input))?;
+        Ok(Thermo::new(parse_path(cells)?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Arrow {
+    pub circle: Cell,
+    /// The cells along the arrow, starting next to the circle.
+    pub cells: Vec<Cell>,
+}
+
+impl Arrow {
+    pub fn new(circle: Cell, cells: Vec<Cell>) -> Self {
+        Self { circle, cells }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.circle == cell || self.cells.contains(&cell)
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        let mut path = vec![self.circle];
+        path.extend(&self.cells);
+        (1..=9).contains(&self.cells.len()) && is_path(&path) && !has_repeats(&path)
+    }
+
+    /// Whether `value` fits at `cell` given the digits placed on the arrow.
+    pub fn allows(&self, sudoku: &Sudoku, cell: Cell, value: u8) -> bool {
+        if !self.contains(cell) {
+            return true;
+        }
+        let circle = digit(sudoku, self.circle, cell, value) as usize;
+        let placed: usize = self
+            .cells
+            .iter()
+            .map(|&c| digit(sudoku, c, cell, value) as usize)
+            .sum();
+        let empty = self
+            .cells
+            .iter()
+            .filter(|&&c| digit(sudoku, c, cell, value) == 0)
+            .count();
+        match circle {
+            0 => placed + empty <= 9,
+            circle => placed + empty <= circle && placed + 9 * empty >= circle,
+        }
+    }
+}
+
+impl fmt::Display for Arrow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arrow:{}={}", self.circle, notation::group(&self.cells))
+    }
+}
+
+impl FromStr for Arrow {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::new(input);
+        let rest = input.strip_prefix("arrow:").ok_or_else(invalid)?;
+        let (circle, cells) = rest.split_once('=').ok_or_else(invalid)?;
+        Ok(Arrow::new(circle.parse()?, parse_path(cells)?))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Sandwich {
+    /// A row or column.
+    pub domain: Domain,
+    pub sum: u8,
+}
+
+impl Sandwich {
+    pub fn new(domain: Domain, sum: u8) -> Self {
+        Self { domain, sum }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        (0..9).map(move |i| self.domain.cell(i))
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.domain.contains(cell)
+    }
+
+    /// Only rows and columns, and sums that distinct digits from 2 to 8 can
+    /// make.
+    pub(crate) fn is_valid(&self) -> bool {
+        matches!(self.domain, Domain::Row(_) | Domain::Col(_)) && self.sum != 1 && self.sum <= 35
+    }
+
+    /// Whether `value` fits at `cell` given the digits placed in the line.
+    pub fn allows(&self, sudoku: &Sudoku, cell: Cell, value: u8) -> bool {
+        if !self.contains(cell) {
+            return true;
+        }
+        let digits: Vec<u8> = self
+            .cells()
+            .map(|c| digit(sudoku, c, cell, value))
+            .collect();
+        let (one, nine) = match (
+            digits.iter().position(|&d| d == 1),
+            digits.iter().position(|&d| d == 9),
+        ) {
+            (Some(one), Some(nine)) => (one.min(nine), one.max(nine)),
+            _ => return true,
+        };
+        let between = &digits[one + 1..nine];
+        let placed: usize = between.iter().map(|&d| d as usize).sum();
+        let empty = between.iter().filter(|&&d| d == 0).count();
+        let sum = self.sum as usize;
+        placed + 2 * empty <= sum && placed + 8 * empty >= sum
+    }
+}
+
+impl fmt::Display for Sandwich {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sandwich:{}={}", self.domain, self.sum)
+    }
+}
+
+impl FromStr for Sandwich {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::new(input);
+        let rest = input.strip_prefix("sandwich:").ok_or_else(invalid)?;
+        let (domain, sum) = rest.split_once('=').ok_or_else(invalid)?;
+        Ok(Sandwich::new(
+            domain.parse()?,
+            sum.parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{util::Domain, Cell, Sudoku};
+
+    use super::{Arrow, Sandwich, Thermo};
+
+    #[test]
+    fn lines_allows() {
+        let thermo: Thermo = "thermo:r1c1,r2c2,r2c3".parse().unwrap();
+        let mut sudoku = Sudoku::default();
+        assert!(thermo.allows(&sudoku, Cell::new(0, 0), 7));
+        assert!(!thermo.allows(&sudoku, Cell::new(0, 0), 8));
+        assert!(!thermo.allows(&sudoku, Cell::new(1, 2), 2));
+        sudoku.set_cell(Cell::new(1, 1), 5);
+        assert!(!thermo.allows(&sudoku, Cell::new(0, 0), 5));
+        assert!(thermo.allows(&sudoku, Cell::new(1, 2), 6));
+
+        let arrow: Arrow = "arrow:r5c5=r4c4,r3c3".parse().unwrap();
+        let mut sudoku = Sudoku::default();
+        assert!(!arrow.allows(&sudoku, Cell::new(4, 4), 1));
+        sudoku.set_cell(Cell::new(4, 4), 6);
+        sudoku.set_cell(Cell::new(3, 3), 4);
+        assert!(arrow.allows(&sudoku, Cell::new(2, 2), 2));
+        assert!(!arrow.allows(&sudoku, Cell::new(2, 2), 3));
+
+        let sandwich = Sandwich::new(Domain::Row(0), 5);
+        let mut sudoku = Sudoku::default();
+        sudoku.set_cell(Cell::new(0, 0), 1);
+        sudoku.set_cell(Cell::new(0, 2), 3);
+        assert!(sandwich.allows(&sudoku, Cell::new(0, 3), 9));
+        assert!(!sandwich.allows(&sudoku, Cell::new(0, 4), 9));
+        assert!(!sandwich.allows(&sudoku, Cell::new(0, 8), 9));
+    }
+
+    #[test]
+    fn lines_notation() {
+        let thermo: Thermo = "thermo:r3c321,r4c1".parse().unwrap();
+        assert_eq!(thermo.cells[0], Cell::new(2, 2));
+        assert!(thermo.is_valid());
+        assert_eq!(thermo.to_string().parse::<Thermo>(), Ok(thermo));
+        assert!(!"thermo:r1c1,r3c3".parse::<Thermo>().unwrap().is_valid());
+
+        let arrow: Arrow = "arrow:r1c1=r1c234".parse().unwrap();
+        assert_eq!(arrow.circle, Cell::new(0, 0));
+        assert_eq!(arrow.cells.len(), 3);
+        assert_eq!(arrow.to_string(), "arrow:r1c1=r1c234");
+
+        let sandwich: Sandwich = "sandwich:c4=12".parse().unwrap();
+        assert_eq!(sandwich, Sandwich::new(Domain::Col(3), 12));
+        assert_eq!(sandwich.to_string().parse::<Sandwich>(), Ok(sandwich));
+        assert!(!"sandwich:b1=12".parse::<Sandwich>().unwrap().is_valid());
+        assert!("sandwich:r1".parse::<Sandwich>().is_err());
+    }
+}
@@ -0,0 +1,376 @@
+//! Puzzles made of several 9×9 grids that share boxes, such as the five
+//! grid Samurai.
+//!
+//! The grids sit on one combined board, a [`Sudoku`] as large as the grids
+//! reach. Its [`Layout`] has the rows, columns and boxes of every grid, with
+//! the regions of grid `g` numbered from `9 * g` and a box shared by two
+//! grids as a single region. The board is solved by the regular pipeline on
+//! that layout, so a deduction in one grid carries over to every grid
+//! sharing the cell and the steps are in board coordinates. Cells outside
+//! every grid aren't part of the puzzle and stay empty.
+
+use std::{fmt, rc::Rc, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    output::{Solve, StepIter},
+    sudoku::Solution,
+    util::Domain,
+    Budget, Cell, Config, Layout, Region, Solver, Sudoku,
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MultiGrid {
+    /// Top left cell of every 9×9 grid.
+    grids: Vec<Cell>,
+    /// Digits of the combined board, `0` for empty and for cells outside
+    /// every grid.
+    sudoku: Sudoku,
+}
+
+impl MultiGrid {
+    /// An empty board with a grid at each of `grids`. Grids that overlap
+    /// must share whole boxes.
+    pub fn new(grids: Vec<Cell>) -> Result<Self, MultiError> {
+        if grids.is_empty() {
+            return Err(MultiError::Empty);
+        }
+        for (i, a) in grids.iter().enumerate() {
+            for b in &grids[..i] {
+                let overlaps = a.row < b.row + 9
+                    && b.row < a.row + 9
+                    && a.col < b.col + 9
+                    && b.col < a.col + 9;
+                let aligned = a.row.abs_diff(b.row) % 3 == 0 && a.col.abs_diff(b.col) % 3 == 0;
+                if overlaps && (!aligned || a == b) {
+                    return Err(MultiError::Overlap(i));
+                }
+            }
+        }
+        let rows = grids.iter().map(|c| c.row + 9).max().unwrap_or_default();
+        let cols = grids.iter().map(|c| c.col + 9).max().unwrap_or_default();
+        Ok(Self {
+            grids,
+            sudoku: Sudoku::new(rows, cols),
+        })
+    }
+
+    /// Four grids around a fifth, each sharing a corner box with the middle
+    /// one.
+    pub fn samurai() -> Self {
+        let grids = [(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+        Self::new(grids.iter().map(|&(r, c)| Cell::new(r, c)).collect()).unwrap()
+    }
+
+    /// Reads the givens in reading order over the cells that lie in a grid,
+    /// ignoring whitespace.
+    pub fn with_givens(grids: Vec<Cell>, givens: &str) -> Result<Self, MultiError> {
+        let mut multi = Self::new(grids)?;
+        let symbols: Vec<char> = givens.chars().filter(|c| !c.is_whitespace()).collect();
+        let cells: Vec<Cell> = multi.cells().collect();
+        if symbols.len() != cells.len() {
+            return Err(MultiError::Givens(symbols.len()));
+        }
+        for (&cell, &symbol) in cells.iter().zip(&symbols) {
+            let value = match symbol {
+                '.' | '0' => 0,
+                c => c.to_digit(10).ok_or(MultiError::Symbol(c))? as u8,
+            };
+            multi.set(cell, value);
+        }
+        Ok(multi)
+    }
+
+    pub fn grids(&self) -> &[Cell] {
+        &self.grids
+    }
+
+    pub fn rows(&self) -> usize {
+        self.sudoku.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.sudoku.cols()
+    }
+
+    /// The combined board.
+    pub fn sudoku(&self) -> &Sudoku {
+        &self.sudoku
+    }
+
+    /// Whether `cell` lies in any grid.
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.grids_at(cell).next().is_some()
+    }
+
+    /// The grids containing `cell` with the cell it is in each of them.
+    pub fn grids_at(&self, cell: Cell) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        self.grids
+            .iter()
+            .enumerate()
+            .filter_map(move |(g, corner)| {
+                let inside = (corner.row..corner.row + 9).contains(&cell.row)
+                    && (corner.col..corner.col + 9).contains(&cell.col);
+                if inside {
+                    Some((g, Cell::new(cell.row - corner.row, cell.col - corner.col)))
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// All cells that lie in a grid, in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.sudoku.cells().filter(move |&c| self.contains(c))
+    }
+
+    pub fn get(&self, cell: Cell) -> u8 {
+        *self.sudoku.cell(cell)
+    }
+
+    pub fn set(&mut self, cell: Cell, value: u8) {
+        assert!(self.contains(cell) && value <= 9);
+        self.sudoku.set_cell(cell, value);
+    }
+
+    /// The digits of grid `grid` as a regular sudoku.
+    pub fn grid(&self, grid: usize) -> Sudoku {
+        let corner = self.grids[grid];
+        let mut sudoku = Sudoku::default();
+        for index in 0..81 {
+            let cell = Cell::from_index(index, 9);
+            let at = Cell::new(corner.row + cell.row, corner.col + cell.col);
+            sudoku.set_cell(cell, self.get(at));
+        }
+        sudoku
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells().all(|c| self.get(c) != 0)
+    }
+
+    /// The rows, columns and boxes of every grid on the combined board.
+    pub fn layout(&self) -> Layout {
+        let mut regions: Vec<Region> = Vec::with_capacity(27 * self.grids.len());
+        for (g, corner) in self.grids.iter().enumerate() {
+            for i in 0..9 {
+                let n = 9 * g + i;
+                for &(local, domain) in &[
+                    (Domain::Row(i), Domain::Row(n)),
+                    (Domain::Col(i), Domain::Col(n)),
+                    (Domain::Sqr(i), Domain::Sqr(n)),
+                ] {
+                    let cells: Vec<Cell> = (0..9)
+                        .map(|j| local.cell(j))
+                        .map(|c| Cell::new(corner.row + c.row, corner.col + c.col))
+                        .collect();
+                    // A shared box keeps the number it has in the first grid.
+                    if !regions.iter().any(|r| r.cells == cells) {
+                        regions.push(Region::new(domain, cells));
+                    }
+                }
+            }
+        }
+        Layout::new(regions)
+    }
+
+    /// The default configuration on the layout of the board.
+    pub fn config(&self) -> Config {
+        Config::for_layout(Rc::new(self.layout()))
+    }
+
+    /// Whether no region repeats a given.
+    fn is_consistent(&self, layout: &Layout) -> bool {
+        self.cells().all(|cell| match self.get(cell) {
+            0 => true,
+            value => layout.peers(cell).iter().all(|&p| self.get(p) != value),
+        })
+    }
+
+    /// Solves the combined board step by step, see [`StepIter`].
+    pub fn steps(&self) -> StepIter {
+        StepIter::new(self.sudoku.clone(), Rc::new(self.config()))
+    }
+
+    /// The steps of the technique pipeline over the whole board.
+    pub fn solve_steps(&self) -> Solve {
+        let config = self.config();
+        if !self.is_consistent(&config.layout) {
+            return Solve::invalid(self.sudoku.clone());
+        }
+        self.sudoku.solve_steps_config(config)
+    }
+
+    /// The solution, when there is one.
+    pub fn solve(&self) -> Option<MultiGrid> {
+        let config = self.config();
+        if !self.is_consistent(&config.layout) {
+            return None;
+        }
+        match self.sudoku.solve_config(config, &Budget::unlimited(), None) {
+            Solution::Complete(sudoku) => Some(Self {
+                grids: self.grids.clone(),
+                sudoku,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Counts solutions, stopping at `limit`.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let config = self.config();
+        if !self.is_consistent(&config.layout) {
+            return 0;
+        }
+        let config = Config {
+            solvers: vec![Solver::Single],
+            ..config
+        };
+        self.sudoku.solutions(config, limit).len()
+    }
+}
+
+/// The digits in reading order over the cells that lie in a grid.
+impl fmt::Display for MultiGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cell in self.cells() {
+            match self.get(cell) {
+                0 => write!(f, ".")?,
+                value => write!(f, "{}", value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the givens of a [`MultiGrid::samurai`].
+impl FromStr for MultiGrid {
+    type Err = MultiError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::with_givens(Self::samurai().grids, input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultiError {
+    /// A board without grids.
+    Empty,
+    /// The grid overlaps an earlier one in part of a box.
+    Overlap(usize),
+    /// The number of givens doesn't match the board.
+    Givens(usize),
+    Symbol(char),
+}
+
+impl fmt::Display for MultiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiError::Empty => write!(f, "board has no grids"),
+            MultiError::Overlap(g) => write!(f, "grid {} doesn't share whole boxes", g + 1),
+            MultiError::Givens(n) => write!(f, "board doesn't have {} cells", n),
+            MultiError::Symbol(c) => write!(f, "invalid symbol '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for MultiError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, ModTarget, Solver, Sudoku};
+
+    use super::{MultiError, MultiGrid};
+
+    #[test]
+    fn multi_board() {
+        let samurai = MultiGrid::samurai();
+        assert_eq!((samurai.rows(), samurai.cols()), (21, 21));
+        assert_eq!(samurai.cells().count(), 5 * 81 - 4 * 9);
+        assert!(!samurai.contains(Cell::new(0, 9)));
+
+        // The top left box of the middle grid is the bottom right box of the
+        // first one.
+        let shared: Vec<_> = samurai.grids_at(Cell::new(7, 8)).collect();
+        assert_eq!(shared, vec![(0, Cell::new(7, 8)), (2, Cell::new(1, 2))]);
+
+        let layout = samurai.layout();
+        assert_eq!(layout.regions().len(), 5 * 27 - 4);
+        assert_eq!(layout.cells().len(), 5 * 81 - 4 * 9);
+        // The shared cell sees along the rows of both grids, the first rows
+        // of two grids side by side don't see each other.
+        assert!(layout.sees(Cell::new(7, 8), Cell::new(7, 0)));
+        assert!(layout.sees(Cell::new(7, 8), Cell::new(7, 14)));
+        assert!(!layout.sees(Cell::new(0, 0), Cell::new(0, 12)));
+
+        let text = format!("5{}", ".".repeat(368));
+        let multi: MultiGrid = text.parse().unwrap();
+        assert_eq!(multi.get(Cell::new(0, 0)), 5);
+        assert_eq!(multi.to_string(), text);
+        assert_eq!("5".parse::<MultiGrid>(), Err(MultiError::Givens(1)));
+
+        let corners = |r, c| vec![Cell::new(0, 0), Cell::new(r, c)];
+        assert!(MultiGrid::new(corners(6, 3)).is_ok());
+        assert_eq!(MultiGrid::new(corners(4, 6)), Err(MultiError::Overlap(1)));
+        assert_eq!(MultiGrid::new(Vec::new()), Err(MultiError::Empty));
+
+        // A digit repeated across the shared box has no solution.
+        let mut clash = MultiGrid::new(corners(6, 6)).unwrap();
+        clash.set(Cell::new(6, 6), 1);
+        clash.set(Cell::new(6, 14), 1);
+        assert_eq!(clash.count_solutions(1), 0);
+        assert!(!clash.solve_steps().end().valid);
+    }
+
+    #[test]
+    fn multi_shared_box() {
+        // The bottom right box of the first grid is the top left box of the
+        // second. The first grid is solved but for its last cell, the second
+        // only has the shared box.
+        let solution = Sudoku::from(
+            "964572831172843659835961274629485713483217965517396482246138597358729146791654328",
+        );
+        let mut multi = MultiGrid::new(vec![Cell::new(0, 0), Cell::new(6, 6)]).unwrap();
+        for index in 0..80 {
+            let cell = Cell::from_index(index, 9);
+            multi.set(cell, *solution.cell(cell));
+        }
+
+        // One iterator steps through the whole board.
+        let solve = multi.solve_steps();
+        let first = solve.iter().find(|s| s.change.has_targets()).unwrap();
+        assert!(
+            first.change.is_target_digit(Cell::new(8, 8), 8),
+            "{}",
+            first.explain()
+        );
+        assert!(solve.iter().all(|s| s.sudoku.rows() == 15));
+        assert_eq!(multi.steps().count(), solve.iter().count());
+
+        // Every step changes the board, so no cell is placed twice.
+        let mut cells: Vec<Cell> = solve
+            .iter()
+            .flat_map(|step| step.change.target.iter())
+            .filter(|t| matches!(t.target, ModTarget::Digit(_)))
+            .map(|t| t.cell)
+            .collect();
+        let count = cells.len();
+        cells.sort_by_key(|c| (c.row, c.col));
+        cells.dedup();
+        assert_eq!(cells.len(), count);
+        assert_eq!(solve.end().solver, Solver::Solved);
+
+        let done = multi.solve().unwrap();
+        assert!(done.is_solved());
+        assert_eq!(&done.sudoku, &solve.end().sudoku);
+        assert_eq!(done.grid(0), solution);
+        for index in 0..9 {
+            let cell = Cell::new(index / 3, index % 3);
+            assert_eq!(
+                done.grid(1).cell(cell),
+                solution.cell(Cell::new(cell.row + 6, cell.col + 6))
+            );
+        }
+    }
+}
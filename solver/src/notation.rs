@@ -0,0 +1,225 @@
+//! Standard RnCn notation for cells, groups and candidates.
+//!
+//! Rows, columns, boxes and digits are numbered from 1:
+//!
+//! - cell: `r4c7`
+//! - group: `r4c789`, `r456c7` or a region such as `r4`, `c7`, `b5`, `d1`
+//! - candidate: `r4c7<>3` (eliminated) or `r4c7=3` (placed)
+
+use std::{fmt, str::FromStr};
+
+use crate::{util::Domain, Cell, CellMod, ModTarget};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    input: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid notation: '{}'", self.input)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Formats a set of cells, using the compact `r4c789` form for cells in a single row or column.
+pub fn group(cells: &[Cell]) -> String {
+    match cells.first() {
+        None => String::new(),
+        Some(first) if cells.len() > 1 && cells.iter().all(|c| c.row == first.row) => {
+            format!(
+                "r{}c{}",
+                first.row + 1,
+                numbers(cells.iter().map(|c| c.col))
+            )
+        }
+        Some(first) if cells.len() > 1 && cells.iter().all(|c| c.col == first.col) => {
+            format!(
+                "r{}c{}",
+                numbers(cells.iter().map(|c| c.row)),
+                first.col + 1
+            )
+        }
+        _ => cells
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
+
+fn numbers(indices: impl Iterator<Item = usize>) -> String {
+    indices.map(|n| (n + 1).to_string()).collect()
+}
+
+/// Parses a group of cells such as `r4c789`, `r45c7`, `b5`, `r4` or `c7`.
+pub fn parse_group(input: &str) -> Result<Vec<Cell>, ParseError> {
+    if let Ok(domain) = input.parse::<Domain>() {
+        return Ok((0..9).map(|i| domain.cell(i)).collect());
+    }
+    let (rows, cols) = split_rc(input).ok_or_else(|| ParseError::new(input))?;
+    let rows = indices(rows).ok_or_else(|| ParseError::new(input))?;
+    let cols = indices(cols).ok_or_else(|| ParseError::new(input))?;
+    Ok(rows
+        .iter()
+        .flat_map(|&row| cols.iter().map(move |&col| Cell::new(row, col)))
+        .collect())
+}
+
+/// Splits `r..c..` into its row and column digit strings.
+fn split_rc(input: &str) -> Option<(&str, &str)> {
+    let rest = input.strip_prefix(['r', 'R'])?;
+    let split = rest.find(['c', 'C'])?;
+    Some((&rest[..split], &rest[split + 1..]))
+}
+
+/// Parses a run of digits `1..=9` into zero based indices.
+fn indices(input: &str) -> Option<Vec<usize>> {
+    if input.is_empty() {
+        return None;
+    }
+    input
+        .chars()
+        .map(|c| match c.to_digit(10) {
+            Some(n @ 1..=9) => Some(n as usize - 1),
+            _ => None,
+        })
+        .collect()
+}
+
+impl FromStr for Cell {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match split_rc(s).and_then(|(r, c)| Some((indices(r)?, indices(c)?))) {
+            Some((rows, cols)) if rows.len() == 1 && cols.len() == 1 => {
+                Ok(Cell::new(rows[0], cols[0]))
+            }
+            _ => Err(ParseError::new(s)),
+        }
+    }
+}
+
+impl FromStr for Domain {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let kind = chars.next().map(|c| c.to_ascii_lowercase());
+        let n = match indices(chars.as_str()) {
+            Some(n) if n.len() == 1 => n[0],
+            _ => return Err(ParseError::new(s)),
+        };
+        match kind {
+            Some('r') => Ok(Domain::Row(n)),
+            Some('c') => Ok(Domain::Col(n)),
+            Some('b') => Ok(Domain::Sqr(n)),
+            Some('d') if n < 2 => Ok(Domain::Diag(n)),
+            _ => Err(ParseError::new(s)),
+        }
+    }
+}
+
+impl FromStr for CellMod {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (cell, digit, place) = if let Some(i) = s.find("<>") {
+            (&s[..i], &s[i + 2..], false)
+        } else if let Some(i) = s.find('=') {
+            (&s[..i], &s[i + 1..], true)
+        } else {
+            return Ok(CellMod::from(s.parse::<Cell>()?));
+        };
+        let cell = cell
+            .trim()
+            .parse::<Cell>()
+            .map_err(|_| ParseError::new(s))?;
+        let digit = match indices(digit.trim()) {
+            Some(d) if d.len() == 1 => d[0] as u8 + 1,
+            _ => return Err(ParseError::new(s)),
+        };
+        Ok(if place {
+            CellMod::digit(cell, digit)
+        } else {
+            CellMod::option(cell, digit)
+        })
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "r{}c{}", self.row + 1, self.col + 1)
+    }
+}
+
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Domain::Row(n) => write!(f, "r{}", n + 1),
+            Domain::Col(n) => write!(f, "c{}", n + 1),
+            Domain::Sqr(n) => write!(f, "b{}", n + 1),
+            Domain::Diag(n) => write!(f, "d{}", n + 1),
+        }
+    }
+}
+
+impl fmt::Display for CellMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cell)?;
+        match self.target {
+            ModTarget::Digit(n) => write!(f, "={}", n),
+            ModTarget::Option(n) => write!(f, "<>{}", n),
+            ModTarget::Cell => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{util::Domain, Cell, CellMod};
+
+    use super::{group, parse_group};
+
+    #[test]
+    fn notation_cell() {
+        assert_eq!(Cell::new(3, 6).to_string(), "r4c7");
+        assert_eq!("r4c7".parse::<Cell>(), Ok(Cell::new(3, 6)));
+        assert!("r0c7".parse::<Cell>().is_err());
+        assert!("r45c7".parse::<Cell>().is_err());
+    }
+
+    #[test]
+    fn notation_group() {
+        let cells = vec![Cell::new(3, 6), Cell::new(3, 7), Cell::new(3, 8)];
+        assert_eq!(group(&cells), "r4c789");
+        assert_eq!(parse_group("r4c789").unwrap(), cells);
+        assert_eq!(
+            parse_group("r45c7").unwrap(),
+            vec![Cell::new(3, 6), Cell::new(4, 6)]
+        );
+        assert_eq!(parse_group("b5").unwrap()[0], Cell::new(3, 3));
+        assert_eq!("b5".parse::<Domain>(), Ok(Domain::Sqr(4)));
+        assert_eq!(Domain::Col(6).to_string(), "c7");
+        assert_eq!("d2".parse::<Domain>(), Ok(Domain::Diag(1)));
+        assert!("d3".parse::<Domain>().is_err());
+    }
+
+    #[test]
+    fn notation_candidate() {
+        let elim = "r4c7<>3".parse::<CellMod>().unwrap();
+        assert_eq!(elim.to_string(), "r4c7<>3");
+        let place = "r4c7=3".parse::<CellMod>().unwrap();
+        assert_eq!(place.to_string(), "r4c7=3");
+        assert!("r4c7<>0".parse::<CellMod>().is_err());
+    }
+}
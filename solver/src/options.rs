@@ -1,26 +1,38 @@
 #![allow(clippy::suspicious_operation_groupings)]
 
-use crate::{output::ser_array::a81, Cell, Sudoku};
+use crate::{layout::Layout, Cell, Sudoku};
 
 use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
 
+/// The candidates of a cell, a set of the digits 1 up to [`CellOptions::MAX`].
 #[derive(Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct CellOptions(u16);
+pub struct CellOptions(u32);
 
 impl CellOptions {
+    /// The largest digit, that of a 16×16 grid.
+    pub const MAX: u8 = 16;
+
+    /// The digits 1 to 9.
     pub fn all() -> Self {
-        Self(0b1111111110)
+        Self::upto(9)
+    }
+
+    /// The digits 1 to `n`.
+    pub fn upto(n: u8) -> Self {
+        assert!(n <= Self::MAX);
+        Self(((1 << n) - 1) << 1)
     }
 
     #[inline(always)]
     pub fn add(&mut self, i: u8) {
-        assert!(i <= 9);
+        assert!(i <= Self::MAX);
         self.0 |= 0x1 << i
     }
 
     #[inline(always)]
     pub fn remove(&mut self, i: u8) -> bool {
-        assert!(i <= 9);
+        assert!(i <= Self::MAX);
         let old = (self.0 & (0x1 << i)) >> i;
         self.0 &= !(0x1 << i);
         old != 0
@@ -33,13 +45,9 @@ impl CellOptions {
 
     #[inline]
     pub fn take(&mut self) -> Option<u8> {
-        for i in 1..=9 {
-            let old = self.remove(i);
-            if old {
-                return Some(i);
-            }
-        }
-        None
+        let first = self.iter().next()?;
+        self.remove(first);
+        Some(first)
     }
 
     #[inline]
@@ -52,58 +60,30 @@ impl CellOptions {
 
     #[inline]
     pub fn len(&self) -> usize {
-        let mut c = 0;
-        for i in 1..=9 {
-            if self.has(i) {
-                c += 1;
-            }
-        }
-        c
+        self.0.count_ones() as usize
     }
 
     #[inline]
     pub fn found(&self) -> Option<u8> {
-        let mut found = None;
-        for i in 1..=9 {
-            if self.has(i) {
-                match found {
-                    Some(_) => return None,
-                    None => {
-                        found = Some(i as u8);
-                    }
-                }
-            }
+        match self.len() {
+            1 => Some(self.0.trailing_zeros() as u8),
+            _ => None,
         }
-        found
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        for i in 1..=9 {
-            if self.has(i) {
-                return false;
-            }
-        }
-        true
+        self.0 == 0
     }
 
     #[inline]
     pub fn is_set(&self, other: &Self) -> bool {
-        for i in 1..=9 {
-            if other.has(i) && !self.has(i) {
-                return false;
-            }
-        }
-        true
+        other.0 & !self.0 == 0
     }
 
     #[inline]
     pub fn combine(&mut self, other: &Self) {
-        for i in 1..=9 {
-            if other.has(i) {
-                self.add(i);
-            }
-        }
+        self.0 |= other.0
     }
 
     pub fn as_pair(&self) -> Option<OptionPair> {
@@ -148,7 +128,7 @@ where
 
 impl Default for CellOptions {
     fn default() -> Self {
-        Self(0b0000000000)
+        Self(0)
     }
 }
 
@@ -183,7 +163,7 @@ impl<'a> Iterator for OptionsIter<'a> {
     type Item = u8;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.i < 9 {
+        while self.i < CellOptions::MAX {
             self.i += 1;
             if self.options.has(self.i) {
                 return Some(self.i);
@@ -195,26 +175,37 @@ impl<'a> Iterator for OptionsIter<'a> {
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+/// The candidates of every cell of a board, in reading order.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Options {
-    #[serde(with = "a81")]
-    cells: [CellOptions; 81],
+    cols: usize,
+    cells: SmallVec<[CellOptions; 81]>,
 }
 
 impl Options {
+    /// `options` in every cell of a board of `rows` by `cols` cells.
+    pub fn new(rows: usize, cols: usize, options: CellOptions) -> Self {
+        Self {
+            cols,
+            cells: smallvec![options; rows * cols],
+        }
+    }
+
     pub fn remove(&mut self, cell: Cell, value: u8) -> bool {
-        self.cells[cell.index()].remove(value)
+        self.cell_mut(cell).remove(value)
     }
 
+    /// Prunes the candidates of `cell` against its row, column and box of
+    /// the standard layout.
     pub fn options(&mut self, cell: Cell, sudoku: &Sudoku) -> CellOptions {
         let value = *sudoku.cell(cell);
         if value != 0 {
             let mut options = CellOptions::default();
             options.add(value);
-            self.cells[cell.index()] = options;
+            *self.cell_mut(cell) = options;
             return options;
         }
-        let options = &mut self.cells[cell.index()];
+        let options = self.cell_mut(cell);
         for value in sudoku.row(cell.row) {
             options.remove(value);
         }
@@ -228,8 +219,47 @@ impl Options {
         *options
     }
 
+    /// Like [`Options::options`], for any layout. Only peers are removed,
+    /// cages, lines, edges and signs are left to their own solvers.
+    pub fn options_in(&mut self, cell: Cell, sudoku: &Sudoku, layout: &Layout) -> CellOptions {
+        let value = *sudoku.cell(cell);
+        if value != 0 {
+            let mut options = CellOptions::default();
+            options.add(value);
+            *self.cell_mut(cell) = options;
+            return options;
+        }
+        let options = self.cell_mut(cell);
+        for &peer in layout.peers(cell) {
+            options.remove(*sudoku.cell(peer));
+        }
+        *options
+    }
+
+    /// The digits of the layout in every cell of its board, except those
+    /// ruled out before any digit is placed by odd and even cells.
+    pub fn for_layout(layout: &Layout) -> Self {
+        let digits = CellOptions::upto(layout.digits());
+        let mut options = Self::new(layout.rows(), layout.cols(), digits);
+        for cell in layout.cells() {
+            if let Some(parity) = layout.parity_of(*cell) {
+                let options = options.cell_mut(*cell);
+                for value in 1..=layout.digits() {
+                    if !parity.allows(value) {
+                        options.remove(value);
+                    }
+                }
+            }
+        }
+        options
+    }
+
     pub fn cell(&self, cell: Cell) -> &CellOptions {
-        &self.cells[9 * cell.row + cell.col]
+        &self.cells[cell.index(self.cols)]
+    }
+
+    pub fn cell_mut(&mut self, cell: Cell) -> &mut CellOptions {
+        &mut self.cells[cell.index(self.cols)]
     }
 
     pub fn cells(&self) -> &[CellOptions] {
@@ -237,25 +267,26 @@ impl Options {
     }
 }
 
+/// Every digit of a standard sudoku.
 impl Default for Options {
     fn default() -> Self {
-        Self {
-            cells: [CellOptions::all(); 81],
-        }
+        Self::new(9, 9, CellOptions::all())
     }
 }
 
 impl std::fmt::Display for Options {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in 0..9 {
-            if row % 3 == 0 {
+        // Only the standard board is split into boxes.
+        let boxed = self.cells.len() == 81 && self.cols == 9;
+        for (row, cells) in self.cells.chunks(self.cols).enumerate() {
+            if boxed && row % 3 == 0 {
                 writeln!(f)?;
             }
-            for col in 0..9 {
-                if col % 3 == 0 {
+            for (col, cell) in cells.iter().enumerate() {
+                if col % 3 == 0 || !boxed {
                     write!(f, "|")?;
                 }
-                write!(f, "{}|", self.cell(Cell { row, col }).len())?;
+                write!(f, "{}|", cell.len())?;
             }
             writeln!(f)?;
         }
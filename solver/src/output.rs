@@ -1,10 +1,16 @@
+use std::{collections::VecDeque, rc::Rc};
+
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 
-use crate::{sudoku::Buffer, Info, Options, Solver, StateMod, Sudoku};
+use crate::{sudoku::Buffer, Cell, CellOptions, Config, Info, Options, Solver, StateMod, Sudoku};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Solve {
     steps: Vec<SolveStep>,
+    /// The budget ran out before the search finished.
+    #[serde(default)]
+    aborted: bool,
 }
 
 impl Solve {
@@ -19,19 +25,44 @@ impl Solve {
     }
 
     pub fn invalid(sudoku: Sudoku) -> Self {
-        let cache = Options::default();
         Self {
-            steps: vec![SolveStep {
-                sudoku,
-                cache,
-                solver: Solver::Incomplete,
-                change: StateMod::default(),
-                guesses: 0,
-                guesses_t: 0,
-                solved: false,
-                correct: true,
-                valid: false,
-            }],
+            steps: vec![SolveStep::invalid(sudoku)],
+            aborted: false,
+        }
+    }
+
+    /// Whether the solve stopped because its budget ran out, rather than
+    /// ending solved, stuck or invalid.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    pub(crate) fn abort(self) -> Self {
+        Self {
+            aborted: true,
+            ..self
+        }
+    }
+
+    pub fn compact(&self) -> CompactSolve {
+        CompactSolve::from(self)
+    }
+}
+
+impl From<CompactSolve> for Solve {
+    fn from(compact: CompactSolve) -> Self {
+        Self {
+            aborted: compact.aborted,
+            ..compact.iter().collect()
+        }
+    }
+}
+
+impl std::iter::FromIterator<SolveStep> for Solve {
+    fn from_iter<T: IntoIterator<Item = SolveStep>>(iter: T) -> Self {
+        Self {
+            steps: iter.into_iter().collect(),
+            aborted: false,
         }
     }
 }
@@ -56,10 +87,13 @@ impl From<Buffer> for Solve {
                         valid,
                         ..
                     } = e.state.info;
-                    let (sudoku, cache) = s.replace((sudoku, cache)).unwrap_or((sudoku, cache));
+                    let (sudoku, cache) = s
+                        .replace((sudoku.clone(), cache.clone()))
+                        .unwrap_or((sudoku, cache));
                     Some(mods.into_iter().scan(None, move |s, m| {
-                        s.get_or_insert((sudoku, cache));
-                        let (sudoku, cache) = s.unwrap();
+                        let (sudoku, cache) = s
+                            .get_or_insert_with(|| (sudoku.clone(), cache.clone()))
+                            .clone();
                         s.iter_mut().for_each(|(s, c)| m.apply(s, c));
                         Some(SolveStep {
                             sudoku,
@@ -76,6 +110,7 @@ impl From<Buffer> for Solve {
                 })
                 .flatten()
                 .collect(),
+            aborted: false,
         }
     }
 }
@@ -93,46 +128,305 @@ pub struct SolveStep {
     pub valid: bool,
 }
 
-#[doc(hidden)]
-pub fn serialize_array<S, T>(array: &[T], serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: serde::Serializer,
-    T: serde::ser::Serialize,
-{
-    array.serialize(serializer)
-}
-
-#[doc(hidden)]
-#[macro_export]
-macro_rules! serde_array {
-    ($m:ident, $n:expr) => {
-        pub mod $m {
-            pub use crate::output::serialize_array as serialize;
-            use serde::{de, Deserialize, Deserializer};
-            use std::{mem, ptr};
-
-            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<[T; $n], D::Error>
-            where
-                D: Deserializer<'de>,
-                T: Deserialize<'de> + 'de,
-            {
-                let slice: Vec<T> = Deserialize::deserialize(deserializer)?;
-                if slice.len() != $n {
-                    return Err(de::Error::custom("input slice has wrong length"));
-                }
-                unsafe {
-                    let mut result: [T; $n] = mem::MaybeUninit::uninit().assume_init();
-                    for (src, dst) in slice.into_iter().zip(&mut result[..]) {
-                        ptr::write(dst, src);
+impl SolveStep {
+    /// The sudoku and options after the change of this step.
+    pub fn applied(&self) -> (Sudoku, Options) {
+        let (mut sudoku, mut cache) = (self.sudoku.clone(), self.cache.clone());
+        self.change.apply(&mut sudoku, &mut cache);
+        (sudoku, cache)
+    }
+
+    fn invalid(sudoku: Sudoku) -> Self {
+        Self {
+            sudoku,
+            cache: Options::default(),
+            solver: Solver::Incomplete,
+            change: StateMod::default(),
+            guesses: 0,
+            guesses_t: 0,
+            solved: false,
+            correct: true,
+            valid: false,
+        }
+    }
+}
+
+/// Delta encoded form of a [`Solve`].
+///
+/// The starting grid and options are stored once, every step only keeps its
+/// `StateMod` and the changes to the state that applying the previous step's
+/// `StateMod` does not account for. Any step can be reconstructed on demand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactSolve {
+    sudoku: Sudoku,
+    cache: Options,
+    steps: Vec<CompactStep>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    aborted: bool,
+}
+
+impl CompactSolve {
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = SolveStep> + '_ {
+        self.steps.iter().scan(
+            None,
+            move |state: &mut Option<(Sudoku, Options, &StateMod)>, step| {
+                let (mut sudoku, mut cache) = match state.take() {
+                    Some((mut sudoku, mut cache, change)) => {
+                        change.apply(&mut sudoku, &mut cache);
+                        (sudoku, cache)
+                    }
+                    None => (self.sudoku.clone(), self.cache.clone()),
+                };
+                step.delta.apply(&mut sudoku, &mut cache);
+                let expanded = step.expand(sudoku.clone(), cache.clone());
+                state.replace((sudoku, cache, &step.change));
+                Some(expanded)
+            },
+        )
+    }
+
+    pub fn step(&self, step: usize) -> Option<SolveStep> {
+        self.iter().nth(step)
+    }
+
+    pub fn end(&self) -> Option<SolveStep> {
+        self.iter().last()
+    }
+}
+
+impl From<&Solve> for CompactSolve {
+    fn from(solve: &Solve) -> Self {
+        let first = solve.steps.first();
+        let sudoku = first.map(|s| s.sudoku.clone()).unwrap_or_default();
+        let cache = first.map(|s| s.cache.clone()).unwrap_or_default();
+        let mut steps = Vec::with_capacity(solve.steps.len());
+        let mut expected = (sudoku.clone(), cache.clone());
+        for step in &solve.steps {
+            steps.push(CompactStep {
+                solver: step.solver,
+                change: step.change.clone(),
+                guesses: step.guesses,
+                guesses_t: step.guesses_t,
+                solved: step.solved,
+                correct: step.correct,
+                valid: step.valid,
+                delta: Delta::between(&expected, (&step.sudoku, &step.cache)),
+            });
+            expected = (step.sudoku.clone(), step.cache.clone());
+            step.change.apply(&mut expected.0, &mut expected.1);
+        }
+        Self {
+            sudoku,
+            cache,
+            steps,
+            aborted: solve.aborted,
+        }
+    }
+}
+
+impl From<Solve> for CompactSolve {
+    fn from(solve: Solve) -> Self {
+        Self::from(&solve)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CompactStep {
+    solver: Solver,
+    change: StateMod,
+    guesses: u32,
+    guesses_t: u32,
+    solved: bool,
+    correct: bool,
+    valid: bool,
+    delta: Delta,
+}
+
+impl CompactStep {
+    fn expand(&self, sudoku: Sudoku, cache: Options) -> SolveStep {
+        SolveStep {
+            sudoku,
+            cache,
+            solver: self.solver,
+            change: self.change.clone(),
+            guesses: self.guesses,
+            guesses_t: self.guesses_t,
+            solved: self.solved,
+            correct: self.correct,
+            valid: self.valid,
+        }
+    }
+}
+
+/// Cells that differ between two states, by index.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Delta {
+    #[serde(default, skip_serializing_if = "SmallVec::is_empty")]
+    digits: SmallVec<[(usize, u8); 4]>,
+    #[serde(default, skip_serializing_if = "SmallVec::is_empty")]
+    options: SmallVec<[(usize, CellOptions); 4]>,
+}
+
+impl Delta {
+    fn between(from: &(Sudoku, Options), to: (&Sudoku, &Options)) -> Self {
+        let mut delta = Self::default();
+        for (index, (a, b)) in from.0.inner().iter().zip(to.0.inner()).enumerate() {
+            if a != b {
+                delta.digits.push((index, *b));
+            }
+        }
+        for (index, (a, b)) in from.1.cells().iter().zip(to.1.cells()).enumerate() {
+            if a != b {
+                delta.options.push((index, *b));
+            }
+        }
+        delta
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku, cache: &mut Options) {
+        let cols = sudoku.cols();
+        for &(index, value) in &self.digits {
+            sudoku.set_cell(Cell::from_index(index, cols), value);
+        }
+        for &(index, options) in &self.options {
+            *cache.cell_mut(Cell::from_index(index, cols)) = options;
+        }
+    }
+}
+
+/// Serializes a [`Solve`] in its compact form, for use with `#[serde(with = "...")]`.
+pub mod compact {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{CompactSolve, Solve};
+
+    pub fn serialize<S>(solve: &Solve, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        CompactSolve::from(solve).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Solve, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CompactSolve::deserialize(deserializer).map(Solve::from)
+    }
+}
+
+/// Lazily yields the steps of a solve while the search is running.
+///
+/// Unlike [`Solve`], which only holds the path that led to the final state,
+/// this follows the search as it unfolds. Steps taken after a guess have
+/// `correct` set to `false`, when a guess turns out wrong the search returns
+/// to it and the next `BackTrace` step shows the following attempt.
+#[derive(Debug, Clone)]
+pub struct StepIter {
+    start: Sudoku,
+    buffer: Buffer,
+    /// State each buffered entry started from, and how many of its mods were yielded.
+    bases: Vec<((Sudoku, Options), usize)>,
+    queue: VecDeque<SolveStep>,
+    done: bool,
+}
+
+impl StepIter {
+    pub fn new(sudoku: Sudoku, config: Rc<Config>) -> Self {
+        let options = Options::for_layout(&config.layout);
+        Self::with_options(sudoku, options, config)
+    }
+
+    pub fn with_options(sudoku: Sudoku, options: Options, config: Rc<Config>) -> Self {
+        let buffer = Buffer::with_options(sudoku.clone(), options, config);
+        let bases = buffer
+            .iter()
+            .map(|e| ((e.sudoku.clone(), e.options.clone()), 0))
+            .collect();
+        Self {
+            start: sudoku,
+            buffer,
+            bases,
+            queue: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Queues the mods the top entry produced since it was last visited.
+    fn collect(&mut self) {
+        let (entry, ((sudoku, cache), emitted)) = match (self.buffer.get(), self.bases.last_mut()) {
+            (Some(entry), Some(base)) => (entry, base),
+            _ => return,
+        };
+        let mut sudoku = sudoku.clone();
+        let mut cache = cache.clone();
+        for (i, m) in entry.info.mods.iter().enumerate() {
+            if i >= *emitted {
+                self.queue.push_back(SolveStep {
+                    sudoku: sudoku.clone(),
+                    cache: cache.clone(),
+                    solver: entry.solver,
+                    change: m.clone(),
+                    guesses: entry.info.guesses,
+                    guesses_t: entry.info.guesses_t,
+                    solved: entry.info.solved,
+                    correct: entry.info.correct,
+                    valid: entry.info.valid,
+                });
+            }
+            m.apply(&mut sudoku, &mut cache);
+        }
+        *emitted = entry.info.mods.len();
+    }
+
+    fn advance(&mut self) {
+        let entry = self.buffer.get().unwrap();
+        if entry.advance() {
+            self.collect();
+            let next = self.buffer.get().unwrap().make_next();
+            let base = (next.sudoku.clone(), next.options.clone());
+            let terminate = self.buffer.push(next).unwrap().terminate();
+            self.bases.push((base, 0));
+            self.collect();
+            self.done = terminate;
+        } else {
+            let mut last_known = false;
+            loop {
+                let old = self.buffer.pop().unwrap();
+                self.bases.pop();
+                last_known |= old.info.correct;
+                if let Some(entry) = self.buffer.get() {
+                    entry.merge_info(&old);
+                    if !entry.verified() {
+                        break;
+                    };
+                } else {
+                    if !last_known {
+                        self.queue.push_back(SolveStep::invalid(self.start.clone()));
                     }
-                    Ok(result)
+                    self.done = true;
+                    break;
                 }
             }
         }
-    };
+    }
 }
 
-#[doc(hidden)]
-pub mod ser_array {
-    serde_array!(a81, 81);
+impl Iterator for StepIter {
+    type Item = SolveStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.queue.is_empty() && !self.done {
+            self.advance();
+        }
+        self.queue.pop_front()
+    }
 }
@@ -0,0 +1,91 @@
+//! Odd/even sudoku: shaded cells that only take odd or only even digits.
+//!
+//! The cells are listed after the parity, `odd:r1c1,r2c34` or `even:r5c5`.
+//! Unlike the other constraints parity doesn't depend on placed digits, the
+//! wrong digits are dropped from the starting options, see
+//! [`Options::for_layout`](crate::Options::for_layout).
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    notation::{self, ParseError},
+    Cell,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Parity {
+    Odd,
+    Even,
+}
+
+impl Parity {
+    pub fn allows(&self, value: u8) -> bool {
+        match self {
+            Parity::Odd => value & 1 == 1,
+            Parity::Even => value & 1 == 0,
+        }
+    }
+}
+
+impl fmt::Display for Parity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parity::Odd => write!(f, "odd"),
+            Parity::Even => write!(f, "even"),
+        }
+    }
+}
+
+impl FromStr for Parity {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "odd" => Ok(Parity::Odd),
+            "even" => Ok(Parity::Even),
+            _ => Err(ParseError::new(input)),
+        }
+    }
+}
+
+/// Reads `parity:cells`, for example `odd:r1c1,r2c34`.
+pub(crate) fn parse(input: &str) -> Result<(Parity, Vec<Cell>), ParseError> {
+    let (parity, groups) = input
+        .split_once(':')
+        .ok_or_else(|| ParseError::new(input))?;
+    let mut cells = Vec::new();
+    for group in groups.split(',') {
+        cells.extend(notation::parse_group(group)?);
+    }
+    Ok((parity.parse()?, cells))
+}
+
+/// Writes `cells` in the form [`parse`] reads.
+pub(crate) fn format(parity: Parity, cells: &[Cell]) -> String {
+    format!("{}:{}", parity, notation::group(cells))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Cell;
+
+    use super::{format, parse, Parity};
+
+    #[test]
+    fn parity_notation() {
+        assert!(Parity::Odd.allows(7) && !Parity::Odd.allows(4));
+        assert!(Parity::Even.allows(8) && !Parity::Even.allows(1));
+
+        let (parity, cells) = parse("even:r1c1,r2c34").unwrap();
+        assert_eq!(parity, Parity::Even);
+        assert_eq!(
+            cells,
+            vec![Cell::new(0, 0), Cell::new(1, 2), Cell::new(1, 3)]
+        );
+        assert_eq!(parse(&format(parity, &cells)), Ok((parity, cells)));
+        assert!(parse("grey:r1c1").is_err());
+        assert!(parse("odd").is_err());
+    }
+}
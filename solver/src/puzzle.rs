@@ -0,0 +1,454 @@
+//! A sudoku together with the rules it is solved under.
+//!
+//! The text form is the 81 givens, optionally followed by the 81 symbol
+//! region map of a jigsaw puzzle, killer cages, the `diagonal`,
+//! `anti-knight` and `anti-king` flags, the thermometers, arrows and sandwiches of
+//! [`crate::lines`], the edge marks and greater-than signs of
+//! [`crate::edges`] and the odd and even cells of [`crate::parity`], all
+//! separated by whitespace:
+//!
+//! ```text
+//! 3.......4..2.6.1...1.9.8.2...5...6...2.....1...9...8...8.3.4.6...4.1.9..5.......7 111223333111222333...
+//! ................................................................................. 3=r1c12 15=r1c3,r2c23 ...
+//! 1.......2..3.......4...5...6....7..8....9..1...2..3..4......5.6.7....8....9...... anti-knight
+//! ................................................................................. diagonal
+//! ................................................................................. thermo:r1c123 sandwich:r3=15
+//! ................................................................................. white:r1c12 x:r45c3 negative:white
+//! ................................................................................. r1c1>r1c2 r2c1<r3c1 odd:r1c13 even:r5c5
+//! ```
+
+use std::{fmt, rc::Rc, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    edges::{Edge, Inequality, Mark},
+    killer::Cage,
+    layout::LayoutError,
+    lines::{Arrow, Sandwich, Thermo},
+    notation::ParseError,
+    parity::{self, Parity},
+    Cell, Config, Layout, Solver, Sudoku,
+};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct Puzzle {
+    pub sudoku: Sudoku,
+    /// Region map of a jigsaw puzzle, see [`Layout::jigsaw`].
+    #[serde(default)]
+    pub regions: Option<String>,
+    #[serde(default)]
+    pub cages: Vec<Cage>,
+    /// Both main diagonals hold every digit once, see [`Layout::diagonal`].
+    #[serde(default)]
+    pub diagonal: bool,
+    /// Equal digits may not be a chess knight's move apart.
+    #[serde(default)]
+    pub anti_knight: bool,
+    /// Equal digits may not be a chess king's move apart.
+    #[serde(default)]
+    pub anti_king: bool,
+    #[serde(default)]
+    pub thermos: Vec<Thermo>,
+    #[serde(default)]
+    pub arrows: Vec<Arrow>,
+    #[serde(default)]
+    pub sandwiches: Vec<Sandwich>,
+    /// Kropki and XV marks between adjacent cells.
+    #[serde(default)]
+    pub edges: Vec<Edge>,
+    /// Marks whose relation is ruled out between unmarked neighbours.
+    #[serde(default)]
+    pub negative: Vec<Mark>,
+    #[serde(default)]
+    pub inequalities: Vec<Inequality>,
+    /// Cells that only take odd digits.
+    #[serde(default)]
+    pub odd: Vec<Cell>,
+    /// Cells that only take even digits.
+    #[serde(default)]
+    pub even: Vec<Cell>,
+}
+
+impl Puzzle {
+    pub fn new(sudoku: Sudoku) -> Self {
+        Self {
+            sudoku,
+            ..Default::default()
+        }
+    }
+
+    pub fn layout(&self) -> Result<Layout, LayoutError> {
+        let mut layout = match &self.regions {
+            Some(map) => Layout::jigsaw(map)?,
+            None => Layout::standard(),
+        };
+        if self.diagonal {
+            layout = layout.with_diagonals();
+        }
+        if !self.cages.is_empty() {
+            layout = layout.with_cages(self.cages.clone())?;
+        }
+        if self.anti_knight {
+            layout = layout.with_anti_knight();
+        }
+        if self.anti_king {
+            layout = layout.with_anti_king();
+        }
+        if !self.thermos.is_empty() {
+            layout = layout.with_thermos(self.thermos.clone())?;
+        }
+        if !self.arrows.is_empty() {
+            layout = layout.with_arrows(self.arrows.clone())?;
+        }
+        if !self.sandwiches.is_empty() {
+            layout = layout.with_sandwiches(self.sandwiches.clone())?;
+        }
+        if !self.edges.is_empty() || !self.negative.is_empty() {
+            layout = layout.with_edges(self.edges.clone(), self.negative.clone())?;
+        }
+        if !self.inequalities.is_empty() {
+            layout = layout.with_inequalities(self.inequalities.clone())?;
+        }
+        layout = layout
+            .with_parity(Parity::Odd, &self.odd)?
+            .with_parity(Parity::Even, &self.even)?;
+        Ok(layout)
+    }
+
+    /// Whether the puzzle is a plain sudoku.
+    pub fn is_standard(&self) -> bool {
+        self.regions.is_none()
+            && self.cages.is_empty()
+            && !self.diagonal
+            && !self.anti_knight
+            && !self.anti_king
+            && self.thermos.is_empty()
+            && self.arrows.is_empty()
+            && self.sandwiches.is_empty()
+            && self.edges.is_empty()
+            && self.negative.is_empty()
+            && self.inequalities.is_empty()
+            && self.odd.is_empty()
+            && self.even.is_empty()
+    }
+
+    /// The default configuration with the layout of this puzzle and the
+    /// techniques for its constraints, see [`Config::for_layout`].
+    pub fn config(&self) -> Result<Config, LayoutError> {
+        Ok(Config::for_layout(if self.is_standard() {
+            Layout::shared()
+        } else {
+            Rc::new(self.layout()?)
+        }))
+    }
+
+    /// Counts the solutions under the rules of the puzzle, stopping once
+    /// `limit` have been found.
+    pub fn count_solutions(&self, limit: usize) -> Result<usize, LayoutError> {
+        let config = Config {
+            solvers: vec![Solver::Single],
+            ..self.config()?
+        };
+        Ok(self.sudoku.solutions(config, limit).len())
+    }
+}
+
+impl From<Sudoku> for Puzzle {
+    fn from(sudoku: Sudoku) -> Self {
+        Self::new(sudoku)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The givens are not 81 cells long.
+    Givens(usize),
+    Layout(LayoutError),
+    /// A token after the givens that can't be read as any of the rules.
+    Token(ParseError),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleError::Givens(n) => write!(f, "puzzle has {} givens, expected 81", n),
+            PuzzleError::Layout(err) => err.fmt(f),
+            PuzzleError::Token(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+impl From<LayoutError> for PuzzleError {
+    fn from(err: LayoutError) -> Self {
+        PuzzleError::Layout(err)
+    }
+}
+
+impl From<ParseError> for PuzzleError {
+    fn from(err: ParseError) -> Self {
+        PuzzleError::Token(err)
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = PuzzleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split_whitespace();
+        let givens = parts.next().unwrap_or_default();
+        if givens.chars().count() != 81 {
+            return Err(PuzzleError::Givens(givens.chars().count()));
+        }
+        let mut puzzle = Puzzle::new(Sudoku::from(givens));
+        for part in parts {
+            if part == "diagonal" {
+                puzzle.diagonal = true;
+            } else if part == "anti-knight" {
+                puzzle.anti_knight = true;
+            } else if part == "anti-king" {
+                puzzle.anti_king = true;
+            } else if part.starts_with("thermo:") {
+                puzzle.thermos.push(part.parse()?);
+            } else if part.starts_with("arrow:") {
+                puzzle.arrows.push(part.parse()?);
+            } else if part.starts_with("sandwich:") {
+                puzzle.sandwiches.push(part.parse()?);
+            } else if let Some(marks) = part.strip_prefix("negative:") {
+                for mark in marks.split(',') {
+                    puzzle.negative.push(mark.parse()?);
+                }
+            } else if ["white:", "black:", "x:", "v:"]
+                .iter()
+                .any(|mark| part.starts_with(mark))
+            {
+                puzzle.edges.push(part.parse()?);
+            } else if part.starts_with("odd:") || part.starts_with("even:") {
+                let (parity, cells) = parity::parse(part)?;
+                match parity {
+                    Parity::Odd => puzzle.odd.extend(cells),
+                    Parity::Even => puzzle.even.extend(cells),
+                }
+            } else if part.contains('>') || part.contains('<') {
+                puzzle.inequalities.push(part.parse()?);
+            } else if part.contains('=') {
+                puzzle.cages.push(part.parse()?);
+            } else if part.chars().count() == 81 {
+                puzzle.regions = Some(part.to_string());
+            } else {
+                return Err(PuzzleError::Token(ParseError::new(part)));
+            }
+        }
+        // Reject malformed region maps and cages early.
+        puzzle.layout()?;
+        Ok(puzzle)
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sudoku.as_string())?;
+        if let Some(map) = &self.regions {
+            write!(f, " {}", map)?;
+        }
+        for cage in &self.cages {
+            write!(f, " {}", cage)?;
+        }
+        if self.diagonal {
+            write!(f, " diagonal")?;
+        }
+        if self.anti_knight {
+            write!(f, " anti-knight")?;
+        }
+        if self.anti_king {
+            write!(f, " anti-king")?;
+        }
+        for thermo in &self.thermos {
+            write!(f, " {}", thermo)?;
+        }
+        for arrow in &self.arrows {
+            write!(f, " {}", arrow)?;
+        }
+        for sandwich in &self.sandwiches {
+            write!(f, " {}", sandwich)?;
+        }
+        for edge in &self.edges {
+            write!(f, " {}", edge)?;
+        }
+        if !self.negative.is_empty() {
+            let marks: Vec<String> = self.negative.iter().map(|m| m.to_string()).collect();
+            write!(f, " negative:{}", marks.join(","))?;
+        }
+        for sign in &self.inequalities {
+            write!(f, " {}", sign)?;
+        }
+        if !self.odd.is_empty() {
+            write!(f, " {}", parity::format(Parity::Odd, &self.odd))?;
+        }
+        if !self.even.is_empty() {
+            write!(f, " {}", parity::format(Parity::Even, &self.even))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        edges::Mark, layout::LayoutError, notation::ParseError, parity::Parity, Cell, Config,
+        Solver,
+    };
+
+    use super::{Puzzle, PuzzleError};
+
+    static MAP: &str =
+        "111223333111222333114222236144455536445555566444566669777888699777888999777888999";
+
+    #[test]
+    fn puzzle_parse() {
+        let givens = format!("{}{}", "1", ".".repeat(80));
+        let plain: Puzzle = givens.parse().unwrap();
+        assert_eq!(plain.regions, None);
+        assert_eq!(*plain.sudoku.cell(Cell::new(0, 0)), 1);
+
+        let jigsaw: Puzzle = format!("{} {}", givens, MAP).parse().unwrap();
+        assert_eq!(jigsaw.to_string().parse::<Puzzle>(), Ok(jigsaw.clone()));
+        assert_eq!(jigsaw.layout().unwrap().box_of(Cell::new(2, 2)), Some(3));
+
+        assert_eq!("123".parse::<Puzzle>(), Err(PuzzleError::Givens(3)));
+        assert_eq!(
+            format!("{} {}", givens, &MAP[1..]).parse::<Puzzle>(),
+            Err(PuzzleError::Token(ParseError::new(&MAP[1..])))
+        );
+        assert_eq!(
+            format!("{} {}", givens, MAP.replace('9', "8")).parse::<Puzzle>(),
+            Err(PuzzleError::Layout(LayoutError::Pieces(8)))
+        );
+        assert_eq!(
+            format!("{} anti-night", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Token(ParseError::new("anti-night")))
+        );
+
+        let killer: Puzzle = format!("{} 3=r1c23 15=r2c1,r3c12", givens).parse().unwrap();
+        assert_eq!(killer.cages.len(), 2);
+        assert_eq!(killer.cages[1].cells.len(), 3);
+        assert_eq!(killer.to_string().parse::<Puzzle>(), Ok(killer.clone()));
+        let layout = killer.layout().unwrap();
+        assert!(layout.sees(Cell::new(1, 0), Cell::new(2, 1)));
+        assert!(matches!(
+            format!("{} 3=r1c2 4=r1c2", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Layout(LayoutError::CageOverlap(1)))
+        ));
+        assert!(matches!(
+            format!("{} 3=x", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Token(_))
+        ));
+
+        let diagonal: Puzzle = format!("{} diagonal", givens).parse().unwrap();
+        assert!(diagonal.diagonal && !diagonal.is_standard());
+        assert_eq!(diagonal.to_string().parse::<Puzzle>(), Ok(diagonal.clone()));
+        assert!(diagonal
+            .layout()
+            .unwrap()
+            .sees(Cell::new(0, 0), Cell::new(8, 8)));
+        let jigsaw_x: Puzzle = format!("{} {} diagonal", givens, MAP).parse().unwrap();
+        let layout = jigsaw_x.layout().unwrap();
+        assert_eq!(layout.box_of(Cell::new(2, 2)), Some(3));
+        assert!(layout.sees(Cell::new(0, 8), Cell::new(8, 0)));
+
+        let chess: Puzzle = format!("{} anti-king anti-knight", givens).parse().unwrap();
+        assert!(chess.anti_king && chess.anti_knight && !chess.is_standard());
+        assert_eq!(chess.to_string().parse::<Puzzle>(), Ok(chess.clone()));
+        assert!(chess
+            .layout()
+            .unwrap()
+            .sees(Cell::new(0, 0), Cell::new(2, 1)));
+
+        let lines: Puzzle = format!("{} thermo:r1c23 arrow:r5c5=r4c4 sandwich:c2=0", givens)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            (
+                lines.thermos.len(),
+                lines.arrows.len(),
+                lines.sandwiches.len()
+            ),
+            (1, 1, 1)
+        );
+        assert!(!lines.is_standard());
+        assert_eq!(lines.to_string().parse::<Puzzle>(), Ok(lines.clone()));
+        assert!(matches!(
+            format!("{} thermo:r1c2,r3c2", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Layout(LayoutError::Thermo(0)))
+        ));
+
+        let edges: Puzzle = format!("{} white:r1c12 x:r45c3 negative:white,v", givens)
+            .parse()
+            .unwrap();
+        assert_eq!(edges.edges.len(), 2);
+        assert_eq!(edges.negative, vec![Mark::White, Mark::V]);
+        assert_eq!(edges.to_string().parse::<Puzzle>(), Ok(edges.clone()));
+        assert!(matches!(
+            format!("{} black:r1c1,r2c2", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Layout(LayoutError::Edge(0)))
+        ));
+        assert!(matches!(
+            format!("{} negative:dot", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Token(_))
+        ));
+
+        let signs: Puzzle = format!("{} r1c1>r1c2 r3c1<r2c1 odd:r1c13 even:r5c5", givens)
+            .parse()
+            .unwrap();
+        assert_eq!(signs.inequalities.len(), 2);
+        assert_eq!(signs.inequalities[1].greater, Cell::new(1, 0));
+        assert_eq!((signs.odd.len(), signs.even.len()), (2, 1));
+        assert!(!signs.is_standard());
+        assert_eq!(signs.to_string().parse::<Puzzle>(), Ok(signs.clone()));
+        assert_eq!(
+            signs.layout().unwrap().parity_of(Cell::new(0, 2)),
+            Some(Parity::Odd)
+        );
+        assert!(matches!(
+            format!("{} r1c1>r1c3", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Layout(LayoutError::Inequality(0)))
+        ));
+        assert_eq!(
+            format!("{} odd:r1c1 even:r1c12", givens).parse::<Puzzle>(),
+            Err(PuzzleError::Layout(LayoutError::Parity(Cell::new(0, 0))))
+        );
+    }
+
+    #[test]
+    fn puzzle_config() {
+        let givens = ".".repeat(81);
+        let plain: Puzzle = givens.parse().unwrap();
+        assert_eq!(plain.config().unwrap().solvers, Config::default().solvers);
+
+        let killer: Puzzle = format!("{} 3=r1c12 r5c5>r5c6", givens).parse().unwrap();
+        let solvers = killer.config().unwrap().solvers;
+        assert_eq!(
+            solvers,
+            vec![
+                Solver::Single,
+                Solver::Elim,
+                Solver::Cage,
+                Solver::Innies,
+                Solver::Inequality,
+                Solver::Set,
+                Solver::XWing,
+                Solver::XYWing,
+            ]
+        );
+
+        // r1c2 is 6, so a cage of 3 over r1c12 leaves nothing for r1c1.
+        let grid =
+            "964572831172843659835961274629485713483217965517396482246138597358729146791654328";
+        let mut puzzle: Puzzle = format!(".{}", &grid[1..]).parse().unwrap();
+        assert_eq!(puzzle.count_solutions(2), Ok(1));
+        puzzle.cages.push("3=r1c12".parse().unwrap());
+        assert_eq!(puzzle.count_solutions(2), Ok(0));
+    }
+}
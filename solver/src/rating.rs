@@ -0,0 +1,579 @@
+//! Difficulty rating based on the techniques a solve needs.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    solvers::{ElimSolver, SetSolver, SingleSolver, XWingSolver, XYWingSolver},
+    util::{Domain, SetDomain},
+    Cell, CellMod, EntrySolver, ModMarking, Region, Solve, Solver, State, StateMod, Sudoku,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Grade {
+    Easy,
+    Medium,
+    Hard,
+    Fiendish,
+    Extreme,
+}
+
+impl Grade {
+    /// The grade of a puzzle whose hardest step used `solver`.
+    pub fn of(solver: Solver) -> Self {
+        match solver {
+            Solver::Init | Solver::Base | Solver::Single | Solver::Solved | Solver::Incomplete => {
+                Grade::Easy
+            }
+            Solver::Elim
+            | Solver::Cage
+            | Solver::Thermo
+            | Solver::Arrow
+            | Solver::Edges
+            | Solver::Inequality => Grade::Medium,
+            Solver::Innies | Solver::Sandwich | Solver::Set => Grade::Hard,
+            Solver::XWing | Solver::XYWing => Grade::Fiendish,
+            Solver::BackTrace => Grade::Extreme,
+        }
+    }
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Solver {
+    /// Effort assigned to a single step of this technique.
+    pub fn weight(&self) -> u32 {
+        match self {
+            Solver::Init | Solver::Incomplete | Solver::Solved => 0,
+            Solver::Base => 1,
+            Solver::Single => 2,
+            Solver::Elim => 5,
+            Solver::Cage => 3,
+            Solver::Innies => 8,
+            Solver::Thermo => 4,
+            Solver::Arrow => 5,
+            Solver::Sandwich => 8,
+            Solver::Edges => 4,
+            Solver::Inequality => 4,
+            Solver::Set => 8,
+            Solver::XWing => 15,
+            Solver::XYWing => 20,
+            Solver::BackTrace => 40,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Rating {
+    /// Total weighted effort over all productive steps.
+    pub score: u32,
+    /// The technique with the highest weight that was needed.
+    pub hardest: Solver,
+    /// Number of productive steps per technique, in order of weight.
+    pub steps: Vec<(Solver, usize)>,
+    pub grade: Grade,
+}
+
+impl Rating {
+    pub fn count(&self, solver: Solver) -> usize {
+        self.steps
+            .iter()
+            .find(|(s, _)| *s == solver)
+            .map_or(0, |(_, n)| *n)
+    }
+}
+
+impl From<&Solve> for Rating {
+    fn from(solve: &Solve) -> Self {
+        let mut score = 0;
+        let mut hardest = Solver::Init;
+        let mut steps: Vec<(Solver, usize)> = Vec::new();
+        for step in solve.iter().filter(|s| s.change.has_targets()) {
+            score += step.solver.weight();
+            if step.solver.weight() > hardest.weight() {
+                hardest = step.solver;
+            }
+            match steps.iter_mut().find(|(s, _)| *s == step.solver) {
+                Some((_, n)) => *n += 1,
+                None => steps.push((step.solver, 1)),
+            }
+        }
+        steps.sort_by_key(|(s, _)| s.weight());
+        Self {
+            score,
+            hardest,
+            steps,
+            grade: Grade::of(hardest),
+        }
+    }
+}
+
+impl Sudoku {
+    pub fn rate(&self) -> Rating {
+        Rating::from(&self.solve_steps())
+    }
+
+    pub fn se_rating(&self) -> SeRating {
+        SeRating::from(self)
+    }
+}
+
+/// A rating on the Sudoku Explainer (SE) scale.
+///
+/// Like SE, the puzzle is solved by always applying the easiest available
+/// step, and rated by the hardest step that was needed. SE's techniques are
+/// implemented up to XY-Wing (4.2), harder puzzles are not rated exactly.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SeRating {
+    /// SE value of the hardest step.
+    pub value: f32,
+    /// `false` when the puzzle needs a step above 4.2, `value` is then the
+    /// hardest step found before getting stuck.
+    pub exact: bool,
+    /// The technique of the hardest step, direct techniques count as the
+    /// technique they extend.
+    pub hardest: Solver,
+}
+
+impl From<&Sudoku> for SeRating {
+    fn from(sudoku: &Sudoku) -> Self {
+        let mut state = State {
+            sudoku: sudoku.clone(),
+            ..Default::default()
+        };
+        let mut value: f32 = 0.0;
+        let mut hardest = Solver::Init;
+        loop {
+            if state.sudoku.inner().iter().all(|&c| c != 0) {
+                return Self {
+                    value,
+                    exact: true,
+                    hardest,
+                };
+            }
+            match se_step(&state) {
+                Some((m, rating)) => {
+                    if rating > value {
+                        value = rating;
+                        hardest = m.solver;
+                    }
+                    m.apply(&mut state.sudoku, &mut state.options);
+                }
+                None => {
+                    return Self {
+                        value,
+                        exact: false,
+                        hardest,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// SE value of a step produced by one of the implemented techniques, `None`
+/// above XY-Wing.
+fn se_value(m: &StateMod, state: &State) -> Option<f32> {
+    let domain = m.marks.iter().find_map(|m| match m {
+        ModMarking::Domain(d) => Some(*d),
+        _ => None,
+    });
+    let empty = |d: Domain| {
+        state.config.layout.region(d).map_or(9, |r| {
+            r.cells
+                .iter()
+                .filter(|&&c| *state.sudoku.cell(c) == 0)
+                .count()
+        })
+    };
+    match m.solver {
+        Solver::Single => match domain {
+            Some(d) if empty(d) == 1 => Some(1.0),
+            Some(Domain::Sqr(_)) => Some(1.2),
+            _ => Some(1.5),
+        },
+        Solver::Base => Some(2.3),
+        Solver::Elim => match domain {
+            Some(Domain::Sqr(_)) => Some(2.6),
+            _ => Some(2.8),
+        },
+        Solver::Set => {
+            let mut cells: Vec<Cell> = m.source.iter().map(|s| s.cell).collect();
+            cells.dedup();
+            let empty = domain.map_or(9, empty);
+            // A naked set is also a hidden set of the remaining cells, SE
+            // reports whichever of the two is easier.
+            let naked = match cells.len() {
+                2 => 3.0,
+                3 => 3.6,
+                _ => f32::MAX,
+            };
+            let hidden = match empty.saturating_sub(cells.len()) {
+                2 => 3.4,
+                3 => 4.0,
+                _ => f32::MAX,
+            };
+            Some(naked.min(hidden)).filter(|&v| v < f32::MAX)
+        }
+        Solver::XWing => Some(3.2),
+        Solver::XYWing => Some(4.2),
+        _ => None,
+    }
+}
+
+/// Runs `f` on a copy of `state`, returning the first mod it produced.
+fn probe(state: &State, tech: Solver, f: impl FnOnce(&mut State)) -> Option<StateMod> {
+    let mut state = state.clone();
+    state.info.tech = tech;
+    f(&mut state);
+    state.info.mods.into_iter().next()
+}
+
+/// The empty cells of `region` that still take `value`.
+fn places(state: &mut State, region: &Region, value: u8) -> Vec<Cell> {
+    region
+        .cells
+        .iter()
+        .copied()
+        .filter(|&c| *state.sudoku.cell(c) == 0 && state.candidates(c).has(value))
+        .collect()
+}
+
+/// Adds the hidden single `m` leaves for `value` in the regions of its
+/// targets, SE's direct form of a technique. `None` without one.
+fn direct(state: &State, mut m: StateMod, value: u8) -> Option<StateMod> {
+    let mut after = state.clone();
+    m.apply(&mut after.sudoku, &mut after.options);
+    let layout = &state.config.layout;
+    let single = m
+        .target
+        .iter()
+        .flat_map(|t| layout.regions_of(t.cell))
+        .find_map(|region| match places(&mut after, region, value)[..] {
+            [cell] => Some(cell),
+            _ => None,
+        })?;
+    m.push_target(CellMod::digit(single, value));
+    Some(m)
+}
+
+/// Direct Pointing and Direct Claiming, a locked candidate that leaves a
+/// hidden single of its digit.
+fn direct_locking(state: &State, from: &Region, to: &Region) -> Option<(StateMod, f32)> {
+    let rating = match from.domain {
+        Domain::Sqr(_) => 1.7,
+        _ => 1.9,
+    };
+    (1..=9).find_map(|value| {
+        let m = probe(state, Solver::Elim, |s| {
+            ElimSolver::test(from, to, value, s)
+        })?;
+        direct(state, m, value).map(|m| (m, rating))
+    })
+}
+
+/// Direct Hidden Pair and Triple, `size` digits confined to as many cells of
+/// `region` whose other candidates leave a hidden single in the region.
+fn direct_hidden(state: &State, region: &Region, size: usize) -> Option<(StateMod, f32)> {
+    let rating = if size == 2 { 2.0 } else { 2.5 };
+    let mut state = state.clone();
+    let positions: Vec<Vec<Cell>> = (0..=9)
+        .map(|v| match v {
+            0 => Vec::new(),
+            v => places(&mut state, region, v),
+        })
+        .collect();
+    let open: Vec<u8> = (1..=9)
+        .filter(|&v| !positions[v as usize].is_empty())
+        .collect();
+    for mask in 0u16..1 << open.len() {
+        if mask.count_ones() as usize != size {
+            continue;
+        }
+        let values: Vec<u8> = (0..open.len())
+            .filter(|i| mask & 1 << i != 0)
+            .map(|i| open[i])
+            .collect();
+        let mut set: Vec<Cell> = Vec::new();
+        for &v in &values {
+            for &c in &positions[v as usize] {
+                if !set.contains(&c) {
+                    set.push(c);
+                }
+            }
+        }
+        if set.len() != size {
+            continue;
+        }
+        for &single in open.iter().filter(|v| !values.contains(v)) {
+            let rest: Vec<Cell> = positions[single as usize]
+                .iter()
+                .copied()
+                .filter(|c| !set.contains(c))
+                .collect();
+            if rest.len() != 1 || positions[single as usize].len() < 2 {
+                continue;
+            }
+            let mut m = StateMod::from(Solver::Set);
+            m.push_mark(ModMarking::Domain(region.domain));
+            for &cell in &set {
+                for value in state.candidates(cell).iter() {
+                    if values.contains(&value) {
+                        m.push_source(CellMod::option(cell, value));
+                    } else {
+                        m.push_target(CellMod::option(cell, value));
+                    }
+                }
+            }
+            m.push_target(CellMod::digit(rest[0], single));
+            return Some((m, rating));
+        }
+    }
+    None
+}
+
+/// A Swordfish on `value`, three lines of kind `d` whose candidates for it
+/// lie in three cross lines.
+fn swordfish(state: &State, d: SetDomain, value: u8) -> Option<StateMod> {
+    let mut state = state.clone();
+    let layout = state.config.layout.clone();
+    let lines: Vec<(usize, Vec<usize>)> = (0..9)
+        .filter_map(|n| {
+            let line = layout.region(d.at(n))?;
+            let at: Vec<usize> = places(&mut state, line, value)
+                .iter()
+                .filter_map(|c| line.cells.iter().position(|l| l == c))
+                .collect();
+            Some((n, at)).filter(|(_, at)| (2..=3).contains(&at.len()))
+        })
+        .collect();
+    for a in 0..lines.len() {
+        for b in a + 1..lines.len() {
+            for c in b + 1..lines.len() {
+                let fish = [&lines[a], &lines[b], &lines[c]];
+                let mut cover: Vec<usize> = fish.iter().flat_map(|l| l.1.clone()).collect();
+                cover.sort_unstable();
+                cover.dedup();
+                if cover.len() != 3 {
+                    continue;
+                }
+                let mut m = StateMod::from(Solver::XWing);
+                for line in &fish {
+                    m.push_mark(ModMarking::Domain(d.at(line.0)));
+                    for &i in &line.1 {
+                        m.push_source(CellMod::option(d.cell(line.0, i), value));
+                    }
+                }
+                for &i in &cover {
+                    for n in (0..9).filter(|n| fish.iter().all(|l| l.0 != *n)) {
+                        let cell = d.cell(n, i);
+                        if *state.sudoku.cell(cell) == 0 && state.candidates(cell).has(value) {
+                            m.push_target(CellMod::option(cell, value));
+                        }
+                    }
+                }
+                if m.has_targets() {
+                    return Some(m);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The easiest of `found` by SE value.
+fn easiest(found: impl Iterator<Item = (StateMod, f32)>) -> Option<(StateMod, f32)> {
+    found.min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+}
+
+/// Finds the easiest step in SE order, together with its SE value.
+fn se_step(state: &State) -> Option<(StateMod, f32)> {
+    let rated = |m: StateMod| se_value(&m, state).map(|v| (m, v));
+    let layout = &state.config.layout;
+    let is_box = |r: &&Region| matches!(r.domain, Domain::Sqr(_));
+
+    // Boxes first, SE rates hidden singles in a box as easier.
+    let regions = layout
+        .regions()
+        .iter()
+        .filter(is_box)
+        .chain(layout.regions().iter().filter(|r| !is_box(r)));
+    let single = easiest(
+        regions
+            .clone()
+            .filter_map(|region| probe(state, Solver::Single, |s| SingleSolver::test(region, s)))
+            .filter_map(rated),
+    );
+    if single.is_some() {
+        return single;
+    }
+
+    // Direct techniques below 2.3 come before naked singles.
+    let mut direct: Vec<(StateMod, f32)> = layout
+        .overlaps()
+        .filter_map(|(from, to)| direct_locking(state, from, to))
+        .collect();
+    for size in 2..=3 {
+        direct.extend(
+            regions
+                .clone()
+                .filter_map(|r| direct_hidden(state, r, size)),
+        );
+    }
+    let (before, after): (Vec<_>, Vec<_>) = direct.into_iter().partition(|(_, v)| *v < 2.3);
+    if let Some(step) = easiest(before.into_iter()) {
+        return Some(step);
+    }
+
+    for cell in state.sudoku.cells() {
+        if *state.sudoku.cell(cell) != 0 {
+            continue;
+        }
+        if let Some(value) = state.clone().candidates(cell).found() {
+            let mut m = StateMod::from(Solver::Base);
+            m.push_target(CellMod::digit(cell, value));
+            return rated(m);
+        }
+    }
+    if let Some(step) = easiest(after.into_iter()) {
+        return Some(step);
+    }
+
+    let overlaps = layout
+        .overlaps()
+        .filter(|(from, _)| is_box(from))
+        .chain(layout.overlaps().filter(|(from, _)| !is_box(from)));
+    for (from, to) in overlaps {
+        for value in 1..=9 {
+            if let Some(m) = probe(state, Solver::Elim, |s| {
+                ElimSolver::test(from, to, value, s)
+            }) {
+                return rated(m);
+            }
+        }
+    }
+
+    let mut found: Vec<(StateMod, f32)> = Vec::new();
+    for region in regions {
+        for &cell in &region.cells {
+            found.extend(
+                probe(state, Solver::Set, |s| SetSolver::test(region, cell, s)).and_then(rated),
+            );
+        }
+    }
+    for nr in 1..=9 {
+        for &d in &[SetDomain::Row, SetDomain::Col] {
+            found.extend(
+                probe(state, Solver::XWing, |s| XWingSolver::test(d, nr, s)).and_then(rated),
+            );
+            found.extend(swordfish(state, d, nr).map(|m| (m, 3.8)));
+        }
+    }
+    found.extend(
+        probe(state, Solver::XYWing, |s| {
+            XYWingSolver.advance(s);
+        })
+        .and_then(rated),
+    );
+    easiest(found.into_iter())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{util::SetDomain, Cell, ModTarget, Solver, State, Sudoku};
+
+    use super::{places, se_step, swordfish, Grade};
+
+    #[test]
+    fn rating_grades() {
+        let easy = Sudoku::from(
+            ".....48..79.58........9.....75....4.1.62.............751.3..2....48....16.24..5..",
+        )
+        .rate();
+        assert!(easy.grade <= Grade::Medium);
+        assert!(easy.score > 0);
+        assert_eq!(easy.count(Solver::BackTrace), 0);
+
+        let extreme = Sudoku::from(
+            "9.4.728.....8.36..8..9.....6.9....1..83..7.....7.....22...385.....729..6...6.....",
+        )
+        .rate();
+        assert_eq!(extreme.hardest, Solver::BackTrace);
+        assert_eq!(extreme.grade, Grade::Extreme);
+        assert!(extreme.score > easy.score);
+    }
+
+    #[test]
+    fn rating_se_direct() {
+        let mut tiers = Vec::new();
+        for puzzle in &[
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+            "6.....8.3.4.7.................5.4.7.3..2.....1.6.......2.....5.....8.6......1....",
+        ] {
+            let sudoku = Sudoku::from(*puzzle);
+            let mut state = State {
+                sudoku: sudoku.clone(),
+                ..Default::default()
+            };
+            while let Some((m, value)) = se_step(&state) {
+                if value > 1.5 && value < 2.6 && value != 2.3 {
+                    // The eliminations leave the placed digit as the only
+                    // place for it in one of its regions.
+                    let placed: Vec<_> = m
+                        .target
+                        .iter()
+                        .filter_map(|t| match t.target {
+                            ModTarget::Digit(n) => Some((t.cell, n)),
+                            _ => None,
+                        })
+                        .collect();
+                    assert_eq!(placed.len(), 1, "{}", value);
+                    let (cell, digit) = placed[0];
+                    let mut after = state.clone();
+                    for t in &m.target {
+                        if let ModTarget::Option(n) = t.target {
+                            after.options.remove(t.cell, n);
+                        }
+                    }
+                    let layout = state.config.layout.clone();
+                    assert!(layout
+                        .regions_of(cell)
+                        .any(|r| places(&mut after, r, digit) == vec![cell]));
+                    let solver = if value < 2.0 {
+                        Solver::Elim
+                    } else {
+                        Solver::Set
+                    };
+                    assert_eq!(m.solver, solver);
+                    if !tiers.contains(&value) {
+                        tiers.push(value);
+                    }
+                }
+                m.apply(&mut state.sudoku, &mut state.options);
+            }
+            assert_eq!(state.sudoku, sudoku.solve_all()[0]);
+        }
+        tiers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(tiers, vec![1.7, 1.9, 2.0]);
+    }
+
+    #[test]
+    fn rating_se_swordfish() {
+        // 1 in rows 1, 5 and 9 only fits columns 1, 4 and 7.
+        let mut state = State::default();
+        for &(row, cols) in &[(0, [0, 3]), (4, [3, 6]), (8, [0, 6])] {
+            for col in (0..9).filter(|c| !cols.contains(c)) {
+                state.options.remove(Cell::new(row, col), 1);
+            }
+        }
+        assert!(swordfish(&state, SetDomain::Col, 1).is_none());
+        let m = swordfish(&state, SetDomain::Row, 1).unwrap();
+        assert_eq!(m.target.len(), 3 * 6);
+        for t in &m.target {
+            assert!([0, 3, 6].contains(&t.cell.col) && ![0, 4, 8].contains(&t.cell.row));
+        }
+    }
+}
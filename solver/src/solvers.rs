@@ -6,19 +6,33 @@ use serde::{Deserialize, Serialize};
 
 #[doc(inline)]
 pub use self::{
+    arrow::ArrowSolver,
     base::StateSolved,
     base::{Backtrace, BaseSolver, StateInit},
+    cage::CageSolver,
+    edges::EdgeSolver,
     elim::ElimSolver,
+    inequality::InequalitySolver,
+    innies::InniesSolver,
+    sandwich::SandwichSolver,
     sets::SetSolver,
     single::SingleSolver,
+    thermo::ThermoSolver,
     xwing::XWingSolver,
     xywing::XYWingSolver,
 };
 
+mod arrow;
 mod base;
+mod cage;
+mod edges;
 mod elim;
+mod inequality;
+mod innies;
+mod sandwich;
 mod sets;
 mod single;
+mod thermo;
 mod xwing;
 mod xywing;
 
@@ -29,6 +43,20 @@ pub enum Solver {
     Base,
     Single,
     Elim,
+    /// Killer cage sum combinations.
+    Cage,
+    /// Rule of 45 for killer cages.
+    Innies,
+    /// Thermometers, digits increase from the bulb.
+    Thermo,
+    /// Arrows, the circle is the sum along the arrow.
+    Arrow,
+    /// Sandwich clues, the sum between the 1 and the 9.
+    Sandwich,
+    /// Kropki dots and XV marks between neighbours.
+    Edges,
+    /// Greater-than signs between neighbours.
+    Inequality,
     Set,
     XWing,
     XYWing,
@@ -43,6 +71,14 @@ impl Default for Solver {
 }
 
 impl Solver {
+    /// Whether this is a logical solving technique, rather than a search or bookkeeping state.
+    pub fn is_technique(&self) -> bool {
+        !matches!(
+            self,
+            Solver::Init | Solver::BackTrace | Solver::Incomplete | Solver::Solved
+        )
+    }
+
     pub fn make(&self) -> Box<dyn EntrySolver> {
         match self {
             Solver::Init => Box::new(StateInit::default()),
@@ -50,6 +86,13 @@ impl Solver {
             Solver::Base => Box::new(BaseSolver::default()),
             Solver::Single => Box::new(SingleSolver::default()),
             Solver::Elim => Box::new(ElimSolver::default()),
+            Solver::Cage => Box::new(CageSolver),
+            Solver::Innies => Box::new(InniesSolver),
+            Solver::Thermo => Box::new(ThermoSolver),
+            Solver::Arrow => Box::new(ArrowSolver),
+            Solver::Sandwich => Box::new(SandwichSolver),
+            Solver::Edges => Box::new(EdgeSolver),
+            Solver::Inequality => Box::new(InequalitySolver),
             Solver::Set => Box::new(SetSolver::default()),
             Solver::XWing => Box::new(XWingSolver::default()),
             Solver::XYWing => Box::new(XYWingSolver::default()),
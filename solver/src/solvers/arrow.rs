@@ -0,0 +1,56 @@
+use crate::{CellMod, CellOptions, EntrySolver, ModMarking, State, StateMod};
+
+/// The circle of an arrow lies between the smallest and largest sums its
+/// cells can make, and each cell on the arrow must leave a reachable sum.
+#[derive(Debug, Copy, Clone)]
+pub struct ArrowSolver;
+
+impl EntrySolver for ArrowSolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        for arrow in config.layout.arrows() {
+            let circle = state.candidates(arrow.circle);
+            let options: Vec<CellOptions> =
+                arrow.cells.iter().map(|&c| state.candidates(c)).collect();
+            let low = |o: &CellOptions| o.iter().next().unwrap_or(10) as usize;
+            let high = |o: &CellOptions| o.iter().last().unwrap_or(0) as usize;
+            let min: usize = options.iter().map(low).sum();
+            let max: usize = options.iter().map(high).sum();
+
+            let mut mods = StateMod::from(state.info.tech);
+            for value in circle.iter() {
+                let value = value as usize;
+                if (value < min || value > max) && state.remove(arrow.circle, value as u8) {
+                    mods.push_target(CellMod::option(arrow.circle, value as u8));
+                }
+            }
+            for (i, &cell) in arrow.cells.iter().enumerate() {
+                let (rest_min, rest_max) = (min - low(&options[i]), max - high(&options[i]));
+                for value in options[i].iter() {
+                    let fits = circle.iter().any(|total| {
+                        let rest = total as i32 - value as i32;
+                        rest >= rest_min as i32 && rest <= rest_max as i32
+                    });
+                    if !fits && state.remove(cell, value) {
+                        mods.push_target(CellMod::option(cell, value));
+                    }
+                }
+            }
+            if mods.has_targets() {
+                mods.push_mark(ModMarking::Cell(arrow.circle));
+                mods.push_source(CellMod::from(arrow.circle));
+                for &cell in &arrow.cells {
+                    mods.push_source(CellMod::from(cell));
+                }
+                state.info.push_mod(mods);
+            }
+        }
+        true
+    }
+}
+
+impl Default for ArrowSolver {
+    fn default() -> Self {
+        Self
+    }
+}
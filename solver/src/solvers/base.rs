@@ -1,4 +1,6 @@
-use crate::{Cell, CellMod, CellOptions, EntrySolver, State, StateMod};
+use smallvec::SmallVec;
+
+use crate::{generate::Rng, Branching, Cell, CellMod, EntrySolver, Shuffle, State, StateMod};
 
 #[derive(Debug, Copy, Clone)]
 pub struct StateInit;
@@ -61,23 +63,26 @@ impl EntrySolver for BaseSolver {
     fn advance(&mut self, state: &mut State) -> bool {
         let mut solved = true;
         let mut mods = StateMod::from(state.info.tech);
-        for row in 0..9 {
-            for col in 0..9 {
-                let cell = Cell::new(row, col);
-                let value = *state.sudoku.cell(cell);
-                if value == 0 {
-                    let options = state.options.options(cell, &state.sudoku);
-                    if let Some(value) = options.found() {
-                        state.update(cell, value);
-                        mods.push_target(CellMod::digit(cell, value));
-                    } else if options.is_empty() {
-                        return false;
-                    } else {
-                        solved = false;
-                    }
+        let config = state.config.clone();
+        for &cell in config.layout.cells() {
+            let value = *state.sudoku.cell(cell);
+            if value == 0 {
+                let options = state.candidates(cell);
+                if let Some(value) = options.found() {
+                    state.update(cell, value);
+                    mods.push_target(CellMod::digit(cell, value));
+                } else if options.is_empty() {
+                    return false;
+                } else {
+                    solved = false;
                 }
             }
         }
+        // Placed digits can break constraints that candidates don't account
+        // for, this branch is then a dead end.
+        if !state.config.layout.is_consistent(&state.sudoku) {
+            return false;
+        }
         if mods.has_targets() {
             state.info.push_mod(mods);
         }
@@ -92,10 +97,14 @@ impl Default for BaseSolver {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Guesses to try in a backtrace step, the next one is at the end.
+type Alternatives = SmallVec<[(Cell, u8); 9]>;
+
+#[derive(Debug, Clone, Default)]
 pub struct Backtrace {
-    cell: Option<Cell>,
-    options: CellOptions,
+    alternatives: Option<Alternatives>,
+    /// Cell of the previous guess, emptied again before the next one.
+    last: Option<Cell>,
 }
 
 impl EntrySolver for Backtrace {
@@ -105,7 +114,7 @@ impl EntrySolver for Backtrace {
             state.info.guesses += 1;
             state.info.guesses_t += 1;
             let mut mods = StateMod::from(state.info.tech);
-            mods.push_target(cell.into());
+            mods.push_target(CellMod::digit(cell, value));
             state.info.push_mod(mods);
             state.update(cell, value);
             true
@@ -121,47 +130,100 @@ impl EntrySolver for Backtrace {
 
 impl Backtrace {
     pub fn next(&mut self, state: &mut State) -> Option<(Cell, u8)> {
-        if let Some(cell) = self.cell {
-            if let Some(value) = self.options.take() {
-                return Some((cell, value));
+        if self.alternatives.is_none() {
+            self.alternatives = Some(Self::branch(state));
+        }
+        if let Some(cell) = self.last.take() {
+            state.update(cell, 0);
+        }
+        let (cell, value) = self.alternatives.as_mut()?.pop()?;
+        self.last = Some(cell);
+        Some((cell, value))
+    }
+
+    /// Picks what to branch on according to [`Branching`].
+    fn branch(state: &mut State) -> Alternatives {
+        let shuffle = state.config.shuffle;
+        let mut rng = shuffle.map(|shuffle| {
+            let filled = state.sudoku.inner().iter().filter(|&&c| c != 0).count();
+            Rng::new(shuffle.seed ^ filled as u64)
+        });
+        let random_cells = matches!(shuffle, Some(s) if s.cells);
+
+        // Candidates are ranked by key, lowest first. Without random tie
+        // breaks the first one with the lowest key is used.
+        let mut best: Vec<Alternatives> = Vec::new();
+        let mut best_key = (usize::MAX, usize::MAX);
+        let mut consider = |key: (usize, usize), alternatives: Alternatives| {
+            if key < best_key {
+                best_key = key;
+                best.clear();
+                best.push(alternatives);
+            } else if key == best_key && random_cells {
+                best.push(alternatives);
             }
-            None
-        } else {
-            let mut candidate: Option<(usize, Cell, CellOptions)> = None;
-            'lowest: for row in 0..9 {
-                for col in 0..9 {
-                    let cell = Cell { row, col };
-                    if *state.sudoku.cell(cell) != 0 {
-                        continue;
-                    };
-                    let options = state.options.options(cell, &state.sudoku);
-                    let len = options.len();
-                    if candidate.is_none() || len < candidate.unwrap().0 {
-                        candidate.replace((len, Cell { row, col }, options));
-                        if len == 2 {
-                            break 'lowest;
+            !random_cells && key <= (2, 0)
+        };
+
+        let config = state.config.clone();
+        match config.branching {
+            Branching::HiddenSingle => {
+                'hidden: for region in config.layout.regions() {
+                    for value in 1..=config.layout.digits() {
+                        if region.cells.iter().any(|&c| *state.sudoku.cell(c) == value) {
+                            continue;
+                        }
+                        let alternatives: Alternatives = region
+                            .cells
+                            .iter()
+                            .filter(|&&c| {
+                                *state.sudoku.cell(c) == 0 && state.candidates(c).has(value)
+                            })
+                            .map(|&c| (c, value))
+                            .collect();
+                        if consider((alternatives.len(), 0), alternatives) {
+                            break 'hidden;
                         }
                     }
                 }
             }
-            if let Some((_, cell, options)) = candidate {
-                self.cell = Some(cell);
-                self.options = options;
-                if let Some(option) = self.options.take() {
-                    return Some((cell, option));
+            branching => {
+                for &cell in config.layout.cells() {
+                    if *state.sudoku.cell(cell) != 0 {
+                        continue;
+                    }
+                    let options = state.candidates(cell);
+                    let key = match branching {
+                        Branching::First => (0, 0),
+                        Branching::Degree => {
+                            let empty = config
+                                .layout
+                                .peers(cell)
+                                .iter()
+                                .filter(|&&c| *state.sudoku.cell(c) == 0)
+                                .count();
+                            (options.len(), config.layout.cells().len() - empty)
+                        }
+                        _ => (options.len(), 0),
+                    };
+                    if consider(key, options.iter().map(|n| (cell, n)).collect()) {
+                        break;
+                    }
                 }
             }
-            None
         }
-    }
-}
 
-impl Default for Backtrace {
-    fn default() -> Self {
-        Self {
-            cell: None,
-            options: CellOptions::all(),
+        let chosen = match (&mut rng, best.len()) {
+            (_, 0) => return Alternatives::new(),
+            (Some(rng), n) if random_cells => rng.below(n),
+            _ => 0,
+        };
+        let mut alternatives = best.swap_remove(chosen);
+        match (&mut rng, shuffle) {
+            (Some(rng), Some(Shuffle { digits: true, .. })) => rng.shuffle(&mut alternatives),
+            _ => alternatives.reverse(),
         }
+        alternatives
     }
 }
 
@@ -0,0 +1,40 @@
+use crate::{CellMod, EntrySolver, ModMarking, State, StateMod};
+
+/// Killer cage sums. The empty cells of a cage keep only the digits of the
+/// combinations that still make its sum with the digits placed in it.
+#[derive(Debug, Copy, Clone)]
+pub struct CageSolver;
+
+impl EntrySolver for CageSolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        for cage in config.layout.cages() {
+            let allowed = cage.candidates(&state.sudoku);
+            let mut mods = StateMod::from(state.info.tech);
+            for &cell in &cage.cells {
+                if *state.sudoku.cell(cell) != 0 {
+                    continue;
+                }
+                for value in state.candidates(cell).iter() {
+                    if !allowed.has(value) && state.remove(cell, value) {
+                        mods.push_target(CellMod::option(cell, value));
+                    }
+                }
+            }
+            if mods.has_targets() {
+                mods.push_mark(ModMarking::Cell(cage.cells[0]));
+                for &cell in &cage.cells {
+                    mods.push_source(CellMod::from(cell));
+                }
+                state.info.push_mod(mods);
+            }
+        }
+        true
+    }
+}
+
+impl Default for CageSolver {
+    fn default() -> Self {
+        Self
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{edges::neighbours, Cell, CellMod, EntrySolver, Layout, State, StateMod};
+
+/// Kropki dots and XV marks. A candidate stays only when the cell on the
+/// other side of an edge has a digit that fits the mark next to it, or, for
+/// unmarked edges under the negative constraint, one that fits no mark.
+#[derive(Debug, Copy, Clone)]
+pub struct EdgeSolver;
+
+impl EntrySolver for EdgeSolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        let layout = &config.layout;
+        for edge in layout.edges() {
+            Self::test(edge.a, edge.b, layout, state);
+            Self::test(edge.b, edge.a, layout, state);
+        }
+        if !layout.negative().is_empty() {
+            for &cell in layout.cells() {
+                for other in neighbours(cell) {
+                    if layout.edge(cell, other).is_none() {
+                        Self::test(cell, other, layout, state);
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+impl EdgeSolver {
+    /// Removes the candidates of `cell` that no candidate of `other` fits.
+    fn test(cell: Cell, other: Cell, layout: &Layout, state: &mut State) {
+        let options = state.candidates(cell);
+        let across = state.candidates(other);
+        let fits = |a: u8, b: u8| match layout.edge(cell, other) {
+            Some(edge) => edge.mark.allows(a, b),
+            None => !layout.negative().iter().any(|m| m.allows(a, b)),
+        };
+
+        let mut mods = StateMod::from(state.info.tech);
+        for value in options.iter() {
+            let supported = across.iter().any(|w| w != value && fits(value, w));
+            if !supported && state.remove(cell, value) {
+                mods.push_target(CellMod::option(cell, value));
+            }
+        }
+        if mods.has_targets() {
+            mods.push_source(CellMod::from(cell));
+            mods.push_source(CellMod::from(other));
+            state.info.push_mod(mods);
+        }
+    }
+}
+
+impl Default for EdgeSolver {
+    fn default() -> Self {
+        Self
+    }
+}
@@ -1,15 +1,14 @@
-use crate::{util::SetDomain, Cell, CellMod, EntrySolver, State, StateMod};
+use crate::{CellMod, EntrySolver, ModMarking, Region, State, StateMod};
 
 #[derive(Debug, Copy, Clone)]
 pub struct ElimSolver;
 
 impl EntrySolver for ElimSolver {
     fn advance(&mut self, state: &mut State) -> bool {
-        for value in 1..=9 {
-            for i in 0..9 {
-                Self::test_sqr(i, value, state);
-                Self::test(i, SetDomain::Row, value, state);
-                Self::test(i, SetDomain::Col, value, state);
+        let config = state.config.clone();
+        for value in 1..=config.layout.digits() {
+            for (from, to) in config.layout.overlaps() {
+                Self::test(from, to, value, state);
             }
         }
         true
@@ -17,35 +16,21 @@ impl EntrySolver for ElimSolver {
 }
 
 impl ElimSolver {
-    fn test_sqr(sqr: usize, value: u8, state: &mut State) {
-        let mut row = None;
-        let mut col = None;
+    /// When every candidate for `value` in `from` lies in `to`, the value
+    /// can be removed from the rest of `to`.
+    pub(crate) fn test(from: &Region, to: &Region, value: u8, state: &mut State) {
         let mut mods = StateMod::from(state.info.tech);
 
-        for i in 0..9 {
-            let cell = Cell::from_sqr(sqr, i);
+        for &cell in &from.cells {
             if *state.sudoku.cell(cell) == value {
                 return;
             }
             if *state.sudoku.cell(cell) != 0 {
                 continue;
             }
-            let options = state.options.options(cell, &state.sudoku);
-            if options.has(value) {
-                if !mods.has_source() {
-                    row = Some(cell.row);
-                    col = Some(cell.col);
-                } else {
-                    if let Some(r) = row {
-                        if r != cell.row {
-                            row = None;
-                        }
-                    }
-                    if let Some(c) = col {
-                        if c != cell.col {
-                            col = None;
-                        }
-                    }
+            if state.candidates(cell).has(value) {
+                if !to.contains(cell) {
+                    return;
                 }
                 mods.push_source(CellMod::option(cell, value));
             }
@@ -55,76 +40,19 @@ impl ElimSolver {
             return;
         }
 
-        if let Some(row) = row {
-            for i in 0..9 {
-                let cell = Cell::new(row, i);
-                if cell.sqr() == sqr || *state.sudoku.cell(cell) != 0 {
-                    continue;
-                }
-                if state.remove(cell, value) {
-                    mods.push_target(CellMod::option(cell, value));
-                }
-            }
-        } else if let Some(col) = col {
-            for i in 0..9 {
-                let cell = Cell::new(i, col);
-                if cell.sqr() == sqr || *state.sudoku.cell(cell) != 0 {
-                    continue;
-                }
-                if state.remove(cell, value) {
-                    mods.push_target(CellMod::option(cell, value));
-                }
-            }
-        }
-
-        if mods.has_targets() {
-            state.info.push_mod(mods);
-        }
-    }
-
-    fn test(n: usize, d: SetDomain, value: u8, state: &mut State) {
-        let mut sqr = None;
-        let mut mods = StateMod::from(state.info.tech);
-
-        for i in 0..9 {
-            let cell = d.cell(n, i);
-            if *state.sudoku.cell(cell) == value {
-                return;
-            }
-            if *state.sudoku.cell(cell) != 0 {
+        mods.push_mark(ModMarking::Domain(from.domain));
+        mods.push_mark(ModMarking::Domain(to.domain));
+        for &cell in &to.cells {
+            if from.contains(cell) || *state.sudoku.cell(cell) != 0 {
                 continue;
             }
-            let options = state.options.options(cell, &state.sudoku);
-            if options.has(value) {
-                if sqr.is_none() {
-                    sqr = Some(cell.sqr());
-                } else if let Some(sqr) = sqr {
-                    if sqr != cell.sqr() {
-                        return;
-                    }
-                }
-                mods.push_source(CellMod::option(cell, value));
-            }
-        }
-
-        if mods.num_sources() < 2 {
-            return;
-        }
-
-        if let Some(sqr) = sqr {
-            for i in 0..9 {
-                let cell = Cell::from_sqr(sqr, i);
-                if d.is(cell, n) || *state.sudoku.cell(cell) != 0 {
-                    continue;
-                }
-                if state.remove(cell, value) {
-                    mods.push_target(CellMod::option(cell, value));
-                }
+            if state.remove(cell, value) {
+                mods.push_target(CellMod::option(cell, value));
             }
         }
 
         if mods.has_targets() {
-            state.info.push_mod(mods)
+            state.info.push_mod(mods);
         }
     }
 }
@@ -0,0 +1,44 @@
+use crate::{edges::Inequality, Cell, CellMod, EntrySolver, State, StateMod};
+
+/// Greater-than signs. The larger cell keeps only digits above the smallest
+/// candidate across the sign, the smaller cell only digits below the largest.
+#[derive(Debug, Copy, Clone)]
+pub struct InequalitySolver;
+
+impl EntrySolver for InequalitySolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        for sign in config.layout.inequalities() {
+            Self::test(sign, sign.greater, state);
+            Self::test(sign, sign.less, state);
+        }
+        true
+    }
+}
+
+impl InequalitySolver {
+    /// Removes the candidates of `cell` that no candidate across `sign` fits.
+    fn test(sign: &Inequality, cell: Cell, state: &mut State) {
+        let other = sign.other(cell);
+        let options = state.candidates(cell);
+        let across = state.candidates(other);
+
+        let mut mods = StateMod::from(state.info.tech);
+        for value in options.iter() {
+            if !across.iter().any(|w| sign.fits(cell, value, w)) && state.remove(cell, value) {
+                mods.push_target(CellMod::option(cell, value));
+            }
+        }
+        if mods.has_targets() {
+            mods.push_source(CellMod::from(sign.greater));
+            mods.push_source(CellMod::from(sign.less));
+            state.info.push_mod(mods);
+        }
+    }
+}
+
+impl Default for InequalitySolver {
+    fn default() -> Self {
+        Self
+    }
+}
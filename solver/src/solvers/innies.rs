@@ -0,0 +1,134 @@
+use crate::{Cell, CellMod, CellOptions, EntrySolver, Layout, ModMarking, Region, State, StateMod};
+
+/// The rule of 45 for killer cages.
+///
+/// Every region adds up to 45. Subtracting the cages that lie inside a region
+/// leaves the sum of its other cells, the innies. When cages cover the whole
+/// region, the cells of the cages sticking out of it, the outies, add up to
+/// what those cages hold beyond the region.
+#[derive(Debug, Copy, Clone)]
+pub struct InniesSolver;
+
+impl EntrySolver for InniesSolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        if config.layout.cages().is_empty() {
+            return true;
+        }
+        for region in config.layout.regions() {
+            Self::test(region, state);
+        }
+        true
+    }
+}
+
+impl InniesSolver {
+    pub(crate) fn test(region: &Region, state: &mut State) {
+        let config = state.config.clone();
+        let layout = &config.layout;
+        let mut inside = 0;
+        let mut across = 0;
+        let mut outies = Vec::new();
+        for cage in layout.cages() {
+            let within = cage.cells.iter().filter(|&&c| region.contains(c)).count();
+            if within == cage.cells.len() {
+                inside += cage.sum as i32;
+            } else if within > 0 {
+                across += cage.sum as i32;
+                outies.extend(cage.cells.iter().filter(|&&c| !region.contains(c)));
+            }
+        }
+
+        let mut covered = true;
+        let mut innies = Vec::new();
+        for &cell in &region.cells {
+            match layout.cage_of(cell) {
+                Some(cage) if cage.cells.iter().all(|&c| region.contains(c)) => {}
+                Some(_) => innies.push(cell),
+                None => {
+                    covered = false;
+                    innies.push(cell);
+                }
+            }
+        }
+
+        Self::test_group(region, &innies, 45 - inside, state);
+        if covered {
+            Self::test_group(region, &outies, across - (45 - inside), state);
+        }
+    }
+
+    /// Removes the candidates of `group` that can't be part of any way to
+    /// make `sum`. Only small groups are tried.
+    fn test_group(region: &Region, group: &[Cell], mut sum: i32, state: &mut State) {
+        let mut cells: Vec<(Cell, CellOptions)> = Vec::new();
+        for &cell in group {
+            match *state.sudoku.cell(cell) {
+                0 => cells.push((cell, state.candidates(cell))),
+                value => sum -= value as i32,
+            }
+        }
+        if cells.is_empty() || cells.len() > 4 {
+            return;
+        }
+
+        let config = state.config.clone();
+        let mut mods = StateMod::from(state.info.tech);
+        for i in 0..cells.len() {
+            let (cell, options) = cells[i];
+            for value in options.iter() {
+                let mut fixed = cells.clone();
+                fixed[i].1 = CellOptions::default();
+                fixed[i].1.add(value);
+                if !fits(&fixed, &mut Vec::new(), sum, &config.layout) && state.remove(cell, value)
+                {
+                    mods.push_target(CellMod::option(cell, value));
+                }
+            }
+        }
+
+        if mods.has_targets() {
+            mods.push_mark(ModMarking::Domain(region.domain));
+            for &(cell, _) in &cells {
+                mods.push_source(CellMod::from(cell));
+            }
+            state.info.push_mod(mods);
+        }
+    }
+}
+
+/// Whether the cells after `chosen` can be filled from their candidates so
+/// that everything adds up to `sum`, without repeats between peers.
+fn fits(
+    cells: &[(Cell, CellOptions)],
+    chosen: &mut Vec<(Cell, u8)>,
+    sum: i32,
+    layout: &Layout,
+) -> bool {
+    let (cell, options) = match cells.get(chosen.len()) {
+        Some(&next) => next,
+        None => return sum == 0,
+    };
+    for value in options.iter() {
+        if value as i32 > sum
+            || chosen
+                .iter()
+                .any(|&(c, v)| v == value && layout.sees(c, cell))
+        {
+            continue;
+        }
+        chosen.push((cell, value));
+        let found = fits(cells, chosen, sum - value as i32, layout);
+        chosen.pop();
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+impl Default for InniesSolver {
+    fn default() -> Self {
+        Self
+    }
+}
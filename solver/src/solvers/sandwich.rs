@@ -0,0 +1,75 @@
+use crate::{
+    killer::combinations, lines::Sandwich, CellMod, CellOptions, EntrySolver, ModMarking, State,
+    StateMod,
+};
+
+/// Tries every placement of the 1 and the 9 in the line with every set of
+/// digits that makes the sum between them. Candidates that no placement
+/// uses are removed.
+#[derive(Debug, Copy, Clone)]
+pub struct SandwichSolver;
+
+impl EntrySolver for SandwichSolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        for sandwich in config.layout.sandwiches() {
+            Self::test(sandwich, state);
+        }
+        true
+    }
+}
+
+impl SandwichSolver {
+    fn test(sandwich: &Sandwich, state: &mut State) {
+        let cells: Vec<_> = sandwich.cells().collect();
+        let options: Vec<CellOptions> = cells.iter().map(|&c| state.candidates(c)).collect();
+        let bits = |o: &CellOptions| o.iter().fold(0u16, |bits, v| bits | 1 << v);
+        let ends = 1 << 1 | 1 << 9;
+
+        let mut used = [0u16; 9];
+        for one in (0..9).filter(|&i| options[i].has(1)) {
+            for nine in (0..9).filter(|&i| i != one && options[i].has(9)) {
+                let (lo, hi) = (one.min(nine), one.max(nine));
+                for mask in combinations(hi - lo - 1, sandwich.sum as usize, ends) {
+                    let allowed = |i: usize| match i {
+                        _ if i == one => 1 << 1,
+                        _ if i == nine => 1 << 9,
+                        _ if i > lo && i < hi => mask,
+                        _ => !mask & !ends & bits(&CellOptions::all()),
+                    };
+                    if (0..9).all(|i| bits(&options[i]) & allowed(i) != 0) {
+                        for (i, used) in used.iter_mut().enumerate() {
+                            *used |= allowed(i);
+                        }
+                    }
+                }
+            }
+        }
+        // No placement fits at all, leave the contradiction to the search.
+        if used.iter().all(|&u| u == 0) {
+            return;
+        }
+
+        let mut mods = StateMod::from(state.info.tech);
+        for (i, &cell) in cells.iter().enumerate() {
+            for value in options[i].iter() {
+                if used[i] & 1 << value == 0 && state.remove(cell, value) {
+                    mods.push_target(CellMod::option(cell, value));
+                }
+            }
+        }
+        if mods.has_targets() {
+            mods.push_mark(ModMarking::Domain(sandwich.domain));
+            for &cell in &cells {
+                mods.push_source(CellMod::from(cell));
+            }
+            state.info.push_mod(mods);
+        }
+    }
+}
+
+impl Default for SandwichSolver {
+    fn default() -> Self {
+        Self
+    }
+}
@@ -1,15 +1,14 @@
-use crate::{util::SetDomain, Cell, CellMod, EntrySolver, State, StateMod};
+use crate::{Cell, CellMod, EntrySolver, ModMarking, Region, State, StateMod};
 
 #[derive(Debug, Copy, Clone)]
 pub struct SetSolver;
 
 impl EntrySolver for SetSolver {
     fn advance(&mut self, state: &mut State) -> bool {
-        for domain in 0..9 {
-            for cell in 0..9 {
-                Self::test(domain, cell, SetDomain::Sqr, state);
-                Self::test(domain, cell, SetDomain::Row, state);
-                Self::test(domain, cell, SetDomain::Col, state);
+        let config = state.config.clone();
+        for region in config.layout.regions() {
+            for &cell in &region.cells {
+                Self::test(region, cell, state);
             }
         }
         true
@@ -17,21 +16,19 @@ impl EntrySolver for SetSolver {
 }
 
 impl SetSolver {
-    fn test(d: usize, i: usize, domain: SetDomain, state: &mut State) {
-        let cell = domain.cell(d, i);
+    pub(crate) fn test(region: &Region, cell: Cell, state: &mut State) {
         if *state.sudoku.cell(cell) != 0 {
             return;
         }
-        let options = state.options.options(cell, &state.sudoku);
+        let options = state.candidates(cell);
         let len = options.len();
         let mut set = smallvec::SmallVec::<[Cell; 6]>::new();
         set.push(cell);
-        for c in 0..9 {
-            let cmp = domain.cell(d, c);
+        for &cmp in &region.cells {
             if cmp == cell || *state.sudoku.cell(cmp) != 0 {
                 continue;
             }
-            let other = state.options.options(cmp, &state.sudoku);
+            let other = state.candidates(cmp);
             if options.is_set(&other) {
                 set.push(cmp);
             }
@@ -40,62 +37,29 @@ impl SetSolver {
             return;
         }
         let mut mods = StateMod::from(state.info.tech);
-        for other in 0..9 {
-            let other = domain.cell(d, other);
+        mods.push_mark(ModMarking::Domain(region.domain));
+
+        // The set also locks its digits in every other region that holds all of it.
+        let config = state.config.clone();
+        let regions = config
+            .layout
+            .regions_of(cell)
+            .filter(|r| set.iter().all(|&c| r.contains(c)));
+        for other in regions.flat_map(|r| r.cells.iter().copied()) {
             if set.contains(&other) || *state.sudoku.cell(other) != 0 {
                 continue;
             }
             for value in options.iter() {
                 if state.remove(other, value) {
-                    mods.push_target(CellMod::option(cell, value));
+                    mods.push_target(CellMod::option(other, value));
                 }
             }
         }
 
-        if len <= 3 && domain == SetDomain::Sqr {
-            let mut row = Some(cell.row);
-            let mut col = Some(cell.col);
-            for other in &set {
-                if let Some(r) = row {
-                    if r != other.row {
-                        row = None;
-                    }
-                }
-                if let Some(c) = col {
-                    if c != other.col {
-                        col = None;
-                    }
-                }
-            }
-            if let Some(row) = row {
-                for col in 0..9 {
-                    let other = Cell::new(row, col);
-                    if set.contains(&other) || *state.sudoku.cell(other) != 0 {
-                        continue;
-                    }
-                    for value in options.iter() {
-                        if state.remove(other, value) {
-                            mods.push_target(CellMod::option(cell, value));
-                        }
-                    }
-                }
-            }
-            if let Some(col) = col {
-                for row in 0..9 {
-                    let other = Cell::new(row, col);
-                    if set.contains(&other) || *state.sudoku.cell(other) != 0 {
-                        continue;
-                    }
-                    for value in options.iter() {
-                        if state.remove(other, value) {
-                            mods.push_target(CellMod::option(cell, value));
-                        }
-                    }
-                }
-            }
-        }
         for source in set {
-            mods.push_source(source.into());
+            for value in options.iter() {
+                mods.push_source(CellMod::option(source, value));
+            }
         }
         if mods.has_targets() {
             state.info.push_mod(mods);
@@ -1,14 +1,13 @@
-use crate::{util::Domain, Cell, CellMod, EntrySolver, ModMarking, State, StateMod};
+use crate::{Cell, CellMod, CellOptions, EntrySolver, ModMarking, Region, State, StateMod};
 
 #[derive(Debug, Copy, Clone)]
 pub struct SingleSolver;
 
 impl EntrySolver for SingleSolver {
     fn advance(&mut self, state: &mut State) -> bool {
-        for d in 0..9 {
-            Self::test(Domain::Row(d), state);
-            Self::test(Domain::Col(d), state);
-            Self::test(Domain::Sqr(d), state);
+        let config = state.config.clone();
+        for region in config.layout.regions() {
+            Self::test(region, state);
         }
         true
     }
@@ -22,32 +21,36 @@ enum Found {
 }
 
 impl SingleSolver {
-    fn test(domain: Domain, state: &mut State) {
+    pub(crate) fn test(region: &Region, state: &mut State) {
         let mut mods = StateMod::from(state.info.tech);
-        mods.push_mark(ModMarking::Domain(domain));
+        mods.push_mark(ModMarking::Domain(region.domain));
 
-        let options = (0..9)
-            .map(|i| domain.cell(i))
-            .filter_map(|cell| {
+        let options = region
+            .cells
+            .iter()
+            .filter_map(|&cell| {
                 if *state.sudoku.cell(cell) == 0 {
-                    Some((cell, state.options.options(cell, &state.sudoku)))
+                    Some((cell, state.candidates(cell)))
                 } else {
                     None
                 }
             })
-            .fold([Found::None; 9], |mut a, (cell, options)| {
-                options.iter().for_each(|o| {
-                    debug_assert!(o <= 9);
-                    debug_assert!(o > 0);
-                    let i = (o - 1) as usize;
-                    a[i] = match a[i] {
-                        Found::None => Found::Single(cell),
-                        Found::Single(_) => Found::More,
-                        Found::More => Found::More,
-                    }
-                });
-                a
-            });
+            .fold(
+                [Found::None; CellOptions::MAX as usize],
+                |mut a, (cell, options)| {
+                    options.iter().for_each(|o| {
+                        debug_assert!(o <= CellOptions::MAX);
+                        debug_assert!(o > 0);
+                        let i = (o - 1) as usize;
+                        a[i] = match a[i] {
+                            Found::None => Found::Single(cell),
+                            Found::Single(_) => Found::More,
+                            Found::More => Found::More,
+                        }
+                    });
+                    a
+                },
+            );
 
         for (index, count) in options.iter().enumerate() {
             let value = (index + 1) as u8;
@@ -0,0 +1,50 @@
+use crate::{CellMod, CellOptions, EntrySolver, ModMarking, State, StateMod};
+
+/// Digits strictly increase along a thermometer, so every cell lies above
+/// the lowest candidates before it and below the highest ones after it.
+#[derive(Debug, Copy, Clone)]
+pub struct ThermoSolver;
+
+impl EntrySolver for ThermoSolver {
+    fn advance(&mut self, state: &mut State) -> bool {
+        let config = state.config.clone();
+        for thermo in config.layout.thermos() {
+            let options: Vec<CellOptions> =
+                thermo.cells.iter().map(|&c| state.candidates(c)).collect();
+            let len = options.len();
+            let mut low = vec![0; len];
+            let mut high = vec![10; len];
+            for i in 0..len {
+                let above = if i == 0 { 0 } else { low[i - 1] };
+                low[i] = options[i].iter().find(|&v| v > above).unwrap_or(10);
+            }
+            for i in (0..len).rev() {
+                let below = if i == len - 1 { 10 } else { high[i + 1] };
+                high[i] = options[i].iter().filter(|&v| v < below).last().unwrap_or(0);
+            }
+
+            let mut mods = StateMod::from(state.info.tech);
+            for (i, &cell) in thermo.cells.iter().enumerate() {
+                for value in options[i].iter() {
+                    if (value < low[i] || value > high[i]) && state.remove(cell, value) {
+                        mods.push_target(CellMod::option(cell, value));
+                    }
+                }
+            }
+            if mods.has_targets() {
+                mods.push_mark(ModMarking::Cell(thermo.cells[0]));
+                for &cell in &thermo.cells {
+                    mods.push_source(CellMod::from(cell));
+                }
+                state.info.push_mod(mods);
+            }
+        }
+        true
+    }
+}
+
+impl Default for ThermoSolver {
+    fn default() -> Self {
+        Self
+    }
+}
@@ -1,14 +1,17 @@
-use crate::{util::SetDomain, CellMod, EntrySolver, State, StateMod};
+use crate::{
+    util::{Domain, SetDomain},
+    Cell, CellMod, EntrySolver, Layout, ModMarking, Region, State, StateMod,
+};
 
 #[derive(Debug, Copy, Clone)]
 pub struct RowSet {
-    row: usize,
-    first: Option<usize>,
-    second: Option<usize>,
+    row: Domain,
+    first: Option<Cell>,
+    second: Option<Cell>,
 }
 
 impl RowSet {
-    fn new(row: usize) -> Self {
+    fn new(row: Domain) -> Self {
         RowSet {
             row,
             first: None,
@@ -16,11 +19,11 @@ impl RowSet {
         }
     }
 
-    fn add(&mut self, i: usize) -> bool {
+    fn add(&mut self, cell: Cell) -> bool {
         if self.first.is_none() {
-            self.first = Some(i);
+            self.first = Some(cell);
         } else if self.second.is_none() {
-            self.second = Some(i);
+            self.second = Some(cell);
         } else {
             return false;
         }
@@ -30,10 +33,6 @@ impl RowSet {
     fn valid(&self) -> bool {
         self.first.is_some() && self.second.is_some()
     }
-
-    fn same(&self, other: &Self) -> bool {
-        self.first == other.first && self.second == other.second
-    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -41,7 +40,7 @@ pub struct XWingSolver;
 
 impl EntrySolver for XWingSolver {
     fn advance(&mut self, state: &mut State) -> bool {
-        for nr in 1..=9 {
+        for nr in 1..=state.config.layout.digits() {
             Self::test(SetDomain::Row, nr, state);
             Self::test(SetDomain::Col, nr, state);
         }
@@ -50,14 +49,17 @@ impl EntrySolver for XWingSolver {
 }
 
 impl XWingSolver {
-    fn test(d: SetDomain, nr: u8, state: &mut State) {
+    /// Looks for two lines of kind `d` where `nr` fits in the same two
+    /// lines of the other kind.
+    pub(crate) fn test(d: SetDomain, nr: u8, state: &mut State) {
+        let config = state.config.clone();
+        let layout = &config.layout;
         let mut rows = Vec::new();
-        'n: for n in 0..9 {
-            let mut row = RowSet::new(n);
-            for i in 0..9 {
-                let cell = d.cell(n, i);
-                let options = state.options.options(cell, &state.sudoku);
-                if options.has(nr) && !row.add(i) {
+        'n: for line in layout.regions().iter().filter(|r| r.domain.domain() == d) {
+            let mut row = RowSet::new(line.domain);
+            for &cell in &line.cells {
+                let options = state.candidates(cell);
+                if options.has(nr) && !row.add(cell) {
                     continue 'n;
                 }
             }
@@ -71,37 +73,58 @@ impl XWingSolver {
                     if i == j {
                         continue;
                     }
-                    if rows[i].same(&rows[j]) {
-                        Self::xwing(d, nr, rows[i], rows[j], state);
+                    let (first, second) = (rows[i], rows[j]);
+                    let covers = (
+                        Self::cover(layout, d.other(), first.first, second.first),
+                        Self::cover(layout, d.other(), first.second, second.second),
+                    );
+                    if let (Some(a), Some(b)) = covers {
+                        Self::xwing(nr, first, second, [a, b], state);
                     }
                 }
             }
         }
     }
 
-    fn xwing(d: SetDomain, value: u8, first: RowSet, second: RowSet, state: &mut State) {
-        let d = d.other();
-        let mut mods = StateMod::from(state.info.tech);
-        for i in 0..9 {
-            if i == first.row || i == second.row {
-                continue;
-            }
-
-            let cell = d.cell(first.first.unwrap(), i);
-            if state.remove(cell, value) {
-                mods.push_target(CellMod::option(cell, value));
-            }
+    /// The line of kind `d` through both `a` and `b`.
+    fn cover(layout: &Layout, d: SetDomain, a: Option<Cell>, b: Option<Cell>) -> Option<&Region> {
+        let (a, b) = (a?, b?);
+        layout
+            .regions_of(a)
+            .find(|r| r.domain.domain() == d && r.contains(b))
+    }
 
-            let cell = d.cell(first.second.unwrap(), i);
-            if state.remove(cell, value) {
-                mods.push_target(CellMod::option(cell, value));
+    fn xwing(value: u8, first: RowSet, second: RowSet, covers: [&Region; 2], state: &mut State) {
+        let config = state.config.clone();
+        let (a, b) = match (
+            config.layout.region(first.row),
+            config.layout.region(second.row),
+        ) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return,
+        };
+        // Lines that share cells, as on boards of several grids, don't hold
+        // two separate copies of the digit.
+        if a.cells.iter().any(|&c| b.contains(c)) {
+            return;
+        }
+        let mut mods = StateMod::from(state.info.tech);
+        mods.push_mark(ModMarking::Domain(first.row));
+        mods.push_mark(ModMarking::Domain(second.row));
+        for cover in &covers {
+            for &cell in &cover.cells {
+                if a.contains(cell) || b.contains(cell) {
+                    continue;
+                }
+                if state.remove(cell, value) {
+                    mods.push_target(CellMod::option(cell, value));
+                }
             }
         }
         if mods.has_targets() {
-            mods.push_source(d.cell(first.first.unwrap(), first.row).into());
-            mods.push_source(d.cell(first.second.unwrap(), first.row).into());
-            mods.push_source(d.cell(second.first.unwrap(), second.row).into());
-            mods.push_source(d.cell(second.second.unwrap(), second.row).into());
+            for cell in &[first.first, first.second, second.first, second.second] {
+                mods.push_source(CellMod::option(cell.unwrap(), value));
+            }
             state.info.push_mod(mods);
         }
     }
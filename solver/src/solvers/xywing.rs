@@ -1,22 +1,18 @@
-use crate::{
-    options::OptionPair, util::SetDomain, Cell, CellMod, CellOptions, EntrySolver, State, StateMod,
-};
+use crate::{options::OptionPair, Cell, CellMod, CellOptions, EntrySolver, State, StateMod};
 
 #[derive(Debug, Copy, Clone)]
 pub struct XYWingSolver;
 
 impl EntrySolver for XYWingSolver {
     fn advance(&mut self, state: &mut State) -> bool {
-        for row in 0..9 {
-            for col in 0..9 {
-                let cell = Cell::new(row, col);
-                let c_opts = state.options.options(cell, &state.sudoku);
-                if *state.sudoku.cell(cell) != 0 {
-                    continue;
-                }
-                if let Some(c_pair) = c_opts.as_pair() {
-                    Self::test_cell(cell, c_opts, c_pair, state);
-                }
+        let config = state.config.clone();
+        for &cell in config.layout.cells() {
+            let c_opts = state.candidates(cell);
+            if *state.sudoku.cell(cell) != 0 {
+                continue;
+            }
+            if let Some(c_pair) = c_opts.as_pair() {
+                Self::test_cell(cell, c_opts, c_pair, state);
             }
         }
         true
@@ -25,16 +21,10 @@ impl EntrySolver for XYWingSolver {
 
 impl XYWingSolver {
     fn test_cell(cell: Cell, c_opts: CellOptions, c_pair: OptionPair, state: &mut State) {
+        let config = state.config.clone();
         let mut matches: smallvec::SmallVec<[(OptionPair, Cell); 6]> = smallvec::SmallVec::new();
-        for matching in (0..9)
-            .map(|i| {
-                [SetDomain::Row, SetDomain::Col, SetDomain::Sqr]
-                    .iter()
-                    .map(move |d| d.matching(cell, i))
-            })
-            .flatten()
-        {
-            let m_opts = state.options.options(matching, &state.sudoku);
+        for &matching in config.layout.peers(cell) {
+            let m_opts = state.candidates(matching);
             if matching != cell
                 && *state.sudoku.cell(matching) == 0
                 && c_opts != m_opts
@@ -64,27 +54,24 @@ impl XYWingSolver {
         common: u8,
         state: &mut State,
     ) {
-        if matching.sees(other) || value == common {
+        let config = state.config.clone();
+        let layout = &config.layout;
+        if layout.sees(matching, other) || value == common {
             return;
         }
         let mut mods = StateMod::from(state.info.tech);
-        for d in &[SetDomain::Row, SetDomain::Col, SetDomain::Sqr] {
-            for i in 0..9 {
-                let elim = d.matching(matching, i);
-                if other.sees(elim)
-                    && elim != other
-                    && elim != matching
-                    && elim != cell
-                    && state.remove(elim, value)
-                {
-                    mods.push_target(CellMod::option(elim, value));
-                }
+        for &elim in layout.peers(matching) {
+            if layout.sees(other, elim) && elim != cell && state.remove(elim, value) {
+                mods.push_target(CellMod::option(elim, value));
             }
         }
         if mods.has_targets() {
-            mods.push_source(cell.into());
-            mods.push_source(matching.into());
-            mods.push_source(other.into());
+            for source in &[cell, matching, other] {
+                let options = state.candidates(*source);
+                for option in options.iter() {
+                    mods.push_source(CellMod::option(*source, option));
+                }
+            }
             state.info.push_mod(mods);
         }
     }
@@ -1,37 +1,95 @@
 //!
 
-use std::rc::Rc;
+use std::{
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use crate::{
-    output::{ser_array::a81, Solve},
+    output::{Solve, StepIter},
     util::Domain,
-    Cell, Config, Entry, Info, Options, Solver,
+    Budget, Cell, CellOptions, Config, Entry, Info, Layout, Options, Size, SolveStep, Solver,
 };
 
 use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+
+/// Symbols of the digits 1 to 16 in the one line form.
+pub(crate) const SYMBOLS: &[u8] = b"123456789ABCDEFG";
 
-/// Data structure that holds sudoku data.
-#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+/// Data structure that holds sudoku data, the digits of a board of `rows`
+/// by `cols` cells. A standard sudoku is 9 by 9, see [`Layout`] for the
+/// regions of other boards.
+///
+/// [`Layout`]: crate::Layout
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Sudoku {
-    #[serde(with = "a81")]
-    inner: [u8; 81],
+    rows: usize,
+    cols: usize,
+    inner: SmallVec<[u8; 81]>,
 }
 
 impl Sudoku {
+    /// An empty board of `rows` by `cols` cells.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            inner: smallvec![0; rows * cols],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Every cell of the board in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> {
+        let cols = self.cols;
+        (0..self.inner.len()).map(move |i| Cell::from_index(i, cols))
+    }
+
     pub fn solve(&self) -> Solution {
-        let config = Config::default();
+        self.solve_budget(&Budget::unlimited(), None)
+    }
 
-        let mut buffer = Buffer::new(*self, Rc::new(config));
+    /// Solves the sudoku, giving up once `budget` runs out or `cancel` is set.
+    ///
+    /// An interrupted solve returns [`Solution::Aborted`] with the furthest
+    /// state that was reached without guessing.
+    pub fn solve_budget(&self, budget: &Budget, cancel: Option<&AtomicBool>) -> Solution {
+        self.solve_config(self.config(), budget, cancel)
+    }
+
+    /// Like [`Sudoku::solve_budget`], using a custom technique ladder.
+    pub fn solve_config(
+        &self,
+        config: Config,
+        budget: &Budget,
+        cancel: Option<&AtomicBool>,
+    ) -> Solution {
+        let mut advances = 0;
+        let mut buffer = Buffer::new(self.clone(), Rc::new(config));
         loop {
             let entry = buffer.get().unwrap();
+            if budget.exceeded(advances, entry.info.guesses_t)
+                || matches!(cancel, Some(c) if c.load(Ordering::Relaxed))
+            {
+                return Solution::Aborted(buffer.last_correct().unwrap_or_else(|| self.clone()));
+            }
+            advances += 1;
             if entry.advance() {
                 let next = entry.make_next();
                 let entry = buffer.push(next).unwrap();
                 if entry.terminate() {
                     return match entry.info {
                         Info { valid: false, .. } => Solution::Invalid,
-                        Info { solved: true, .. } => Solution::Complete(entry.sudoku),
-                        Info { solved: false, .. } => Solution::Incomplete(entry.sudoku),
+                        Info { solved: true, .. } => Solution::Complete(entry.sudoku.clone()),
+                        Info { solved: false, .. } => Solution::Incomplete(entry.sudoku.clone()),
                     };
                 }
             } else {
@@ -39,7 +97,7 @@ impl Sudoku {
                 loop {
                     let old = buffer.pop().unwrap();
                     if last_known.is_none() && old.info.correct {
-                        last_known = Some(old.sudoku);
+                        last_known = Some(old.sudoku.clone());
                     }
                     if let Some(entry) = buffer.get() {
                         entry.merge_info(&old);
@@ -57,11 +115,26 @@ impl Sudoku {
     }
 
     pub fn solve_steps(&self) -> Solve {
-        let config = Config::default();
+        self.solve_steps_config(self.config())
+    }
+
+    /// Like [`Sudoku::solve_steps`], using a custom technique ladder.
+    pub fn solve_steps_config(&self, config: Config) -> Solve {
+        self.solve_steps_budget(config, &Budget::unlimited())
+    }
 
-        let mut buffer = Buffer::new(*self, Rc::new(config));
+    /// Like [`Sudoku::solve_steps_config`], stopping once `budget` runs out.
+    /// An interrupted solve ends at the state the search had reached and is
+    /// marked [`Solve::is_aborted`].
+    pub fn solve_steps_budget(&self, config: Config, budget: &Budget) -> Solve {
+        let mut advances = 0;
+        let mut buffer = Buffer::new(self.clone(), Rc::new(config));
         loop {
             let entry = buffer.get().unwrap();
+            if budget.exceeded(advances, entry.info.guesses_t) {
+                return Solve::from(buffer).abort();
+            }
+            advances += 1;
             if entry.advance() {
                 let next = entry.make_next();
                 let entry = buffer.push(next).unwrap();
@@ -84,20 +157,72 @@ impl Sudoku {
                     } else if let Some(last) = last_known {
                         return Solve::from(last);
                     } else {
-                        return Solve::invalid(*self);
+                        return Solve::invalid(self.clone());
                     }
                 }
             }
         }
     }
 
+    /// Solves the sudoku step by step, see [`StepIter`].
+    pub fn steps(&self) -> StepIter {
+        StepIter::new(self.clone(), Rc::new(self.config()))
+    }
+
+    /// Finds the next logical deduction, starting from the player's pencil
+    /// marks in `options` when given.
+    ///
+    /// Returns `None` when the sudoku is solved or no technique applies
+    /// without guessing.
+    pub fn hint(&self, options: Option<&Options>) -> Option<SolveStep> {
+        self.hint_config(options, self.config())
+    }
+
+    /// Like [`Sudoku::hint`], using a custom configuration.
+    pub fn hint_config(&self, options: Option<&Options>, config: Config) -> Option<SolveStep> {
+        let options = options
+            .cloned()
+            .unwrap_or_else(|| Options::for_layout(&config.layout));
+        StepIter::with_options(self.clone(), options, Rc::new(config))
+            .filter(|step| step.solver != Solver::Init)
+            .find(|step| step.change.has_targets() || !step.solver.is_technique())
+            .filter(|step| step.solver.is_technique())
+    }
+
     pub fn solve_all(&self) -> Vec<Sudoku> {
+        self.solutions(self.config(), 1000)
+    }
+
+    /// Counts the solutions, stopping once `limit` have been found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let config = Config {
+            solvers: vec![Solver::Single],
+            ..self.config()
+        };
+        self.solutions(config, limit).len()
+    }
+
+    /// The default configuration for the board, on the layout of its
+    /// [`Size`] when that isn't the standard one.
+    pub fn config(&self) -> Config {
+        match Size::from_cells(self.inner.len()) {
+            Some(size) if size != Size::NINE && size.n() == self.rows => {
+                Config::for_layout(Rc::new(Layout::sized(size)))
+            }
+            _ => Config::default(),
+        }
+    }
+
+    pub fn is_unique(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    pub(crate) fn solutions(&self, config: Config, limit: usize) -> Vec<Sudoku> {
         let mut solutions = Vec::new();
-        let config = Config::default();
 
-        let mut buffer = Buffer::new(*self, Rc::new(config));
+        let mut buffer = Buffer::new(self.clone(), Rc::new(config));
         loop {
-            if solutions.len() >= 1000 {
+            if solutions.len() >= limit {
                 return solutions;
             }
 
@@ -106,7 +231,7 @@ impl Sudoku {
                 let next = entry.make_next();
                 let entry = buffer.push(next).unwrap();
                 if entry.terminate() && entry.info.valid && entry.info.solved {
-                    solutions.push(entry.sudoku);
+                    solutions.push(entry.sudoku.clone());
                 }
             } else {
                 loop {
@@ -125,48 +250,52 @@ impl Sudoku {
     }
 
     pub fn cell(&self, cell: Cell) -> &u8 {
-        &self.inner[cell.index()]
+        &self.inner[cell.index(self.cols)]
     }
 
     pub fn cell_mut(&mut self, cell: Cell) -> &mut u8 {
-        &mut self.inner[cell.index()]
+        &mut self.inner[cell.index(self.cols)]
     }
 
     pub fn set_cell(&mut self, cell: Cell, value: u8) {
         *self.cell_mut(cell) = value
     }
 
-    pub fn row(&self, row: usize) -> SudokuIter {
+    pub fn row(&self, row: usize) -> SudokuIter<'_> {
         SudokuIter {
             sudoku: self,
             iter: Domain::Row(row),
+            len: self.cols,
             i: 0,
         }
     }
 
-    pub fn col(&self, col: usize) -> SudokuIter {
+    pub fn col(&self, col: usize) -> SudokuIter<'_> {
         SudokuIter {
             sudoku: self,
             iter: Domain::Col(col),
+            len: self.rows,
             i: 0,
         }
     }
 
-    pub fn sqr(&self, sqr: usize) -> SudokuIter {
+    /// The box `sqr` of the standard 9×9 layout.
+    pub fn sqr(&self, sqr: usize) -> SudokuIter<'_> {
         SudokuIter {
             sudoku: self,
             iter: Domain::Sqr(sqr),
+            len: 9,
             i: 0,
         }
     }
 
-    pub fn as_string(self) -> String {
+    /// The one line form, digits above 9 are the letters `A` to `G`.
+    pub fn as_string(&self) -> String {
         let mut output = String::new();
-        for cell in self.inner.iter() {
-            if *cell > 0 {
-                output.push_str(&format!("{}", cell));
-            } else {
-                output.push('.');
+        for &cell in self.inner.iter() {
+            match cell {
+                0 => output.push('.'),
+                v => output.push(SYMBOLS[v as usize - 1] as char),
             }
         }
         output
@@ -179,24 +308,20 @@ impl Sudoku {
 
 impl Default for Sudoku {
     fn default() -> Self {
-        Self { inner: [0; 81] }
-    }
-}
-
-impl PartialEq for Sudoku {
-    fn eq(&self, other: &Self) -> bool {
-        self.inner == other.inner
+        Self::new(9, 9)
     }
 }
 
 impl std::fmt::Display for Sudoku {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        for row in 0..9 {
-            if row % 3 == 0 {
+        // Only the standard board is split into boxes.
+        let boxed = (self.rows, self.cols) == (9, 9);
+        for row in 0..self.rows {
+            if boxed && row % 3 == 0 {
                 writeln!(f)?;
             }
             for (col, cell) in self.row(row).enumerate() {
-                if col % 3 == 0 {
+                if col % 3 == 0 || !boxed {
                     write!(f, "|")?;
                 }
                 write!(f, "{}|", cell)?;
@@ -207,22 +332,25 @@ impl std::fmt::Display for Sudoku {
     }
 }
 
+/// Reads the one line form of a square board, the side follows from the
+/// length. Symbols that aren't a digit of the board are empty cells.
 impl<T> From<T> for Sudoku
 where
     T: AsRef<str>,
 {
     fn from(input: T) -> Self {
-        assert!(input.as_ref().len() == 81);
-        let mut sudoku = [0; 81];
-        for (cell, output) in input.as_ref().chars().zip(sudoku.iter_mut()) {
-            let value = cell.to_string().parse::<u8>();
-            if let Ok(value) = value {
-                if value > 0 && value <= 9 {
-                    *output = value;
-                }
+        let input = input.as_ref();
+        let n = (1..=CellOptions::MAX as usize)
+            .find(|n| n * n == input.len())
+            .expect("no square board has this many cells");
+        let mut sudoku = Sudoku::new(n, n);
+        for (cell, output) in input.bytes().zip(sudoku.inner.iter_mut()) {
+            match SYMBOLS.iter().position(|&s| s == cell.to_ascii_uppercase()) {
+                Some(i) if i < n => *output = i as u8 + 1,
+                _ => {}
             }
         }
-        Sudoku { inner: sudoku }
+        sudoku
     }
 }
 
@@ -230,6 +358,7 @@ where
 pub struct SudokuIter<'a> {
     sudoku: &'a Sudoku,
     iter: Domain,
+    len: usize,
     i: usize,
 }
 
@@ -237,7 +366,7 @@ impl<'a> Iterator for SudokuIter<'a> {
     type Item = u8;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let out = if self.i == 9 {
+        let out = if self.i == self.len {
             None
         } else {
             Some(*self.sudoku.cell(self.iter.cell(self.i)))
@@ -255,8 +384,13 @@ pub struct Buffer {
 
 impl Buffer {
     pub fn new(sudoku: Sudoku, config: Rc<Config>) -> Self {
+        let options = Options::for_layout(&config.layout);
+        Self::with_options(sudoku, options, config)
+    }
+
+    pub fn with_options(sudoku: Sudoku, options: Options, config: Rc<Config>) -> Self {
         let mut buffer = Vec::with_capacity(32);
-        let state = Entry::new(sudoku, Options::default(), Solver::Init, config);
+        let state = Entry::new(sudoku, options, Solver::Init, config);
         buffer.push(state);
         Self { buffer }
     }
@@ -277,18 +411,35 @@ impl Buffer {
     pub fn into_inner(self) -> Vec<Entry> {
         self.buffer
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry> {
+        self.buffer.iter()
+    }
+
+    /// The deepest state in the buffer that did not depend on a guess.
+    pub fn last_correct(&self) -> Option<Sudoku> {
+        self.buffer
+            .iter()
+            .rev()
+            .find(|e| e.info.correct)
+            .map(|e| e.sudoku.clone())
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum Solution {
     Complete(Sudoku),
     Incomplete(Sudoku),
     Invalid,
+    /// The budget ran out or the solve was cancelled before finishing.
+    Aborted(Sudoku),
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Sudoku;
+    use std::{sync::atomic::AtomicBool, time::Duration};
+
+    use crate::{sudoku::Solution, Budget, Cell, Config, Options, Solve, Sudoku};
 
     #[test]
     fn sudoku_solve_all() {
@@ -298,4 +449,131 @@ mod test {
         let solutions = sudoku.solve_all();
         assert_eq!(solutions.len(), 235);
     }
+
+    #[test]
+    fn sudoku_hint() {
+        let sudoku = Sudoku::from(
+            "9.4.728.....8.36..8..9.....6.9....1..83..7.....7.....22...385.....729..6...6.....",
+        );
+        let hint = sudoku.hint(None).unwrap();
+        assert!(hint.change.has_targets());
+        assert_eq!(hint.sudoku, sudoku);
+
+        let solved = match sudoku.solve() {
+            Solution::Complete(solved) => solved,
+            other => panic!("expected a solution, got {:?}", other),
+        };
+        assert!(solved.hint(None).is_none());
+    }
+
+    #[test]
+    fn sudoku_hint_options() {
+        let sudoku = Sudoku::from(
+            "9.4.728.....8.36..8..9.....6.9....1..83..7.....7.....22...385.....729..6...6.....",
+        );
+        let plain = sudoku.hint(None).unwrap();
+
+        // Striking one of two candidates in the pencil marks leaves a single.
+        let mut options = Options::default();
+        let (cell, cands) = sudoku
+            .cells()
+            .filter(|&c| *sudoku.cell(c) == 0 && !plain.change.is_target(c))
+            .map(|c| (c, options.options(c, &sudoku)))
+            .find(|(_, cands)| cands.len() == 2)
+            .unwrap();
+        let mut cands = cands.iter();
+        let (removed, kept) = (cands.next().unwrap(), cands.next().unwrap());
+        options.remove(cell, removed);
+
+        let hint = sudoku.hint(Some(&options)).unwrap();
+        assert!(hint.change.is_target_digit(cell, kept));
+        assert!(!plain.change.is_target_digit(cell, kept));
+
+        let (next, mut marks) = hint.applied();
+        assert_eq!(*next.cell(cell), kept);
+        assert!(!marks.options(cell, &sudoku).has(removed));
+    }
+
+    #[test]
+    fn sudoku_sized() {
+        // A 4x4 board takes the layout of its size, 16x16 reads letters.
+        let four = Sudoku::from("1.....3..4.....2");
+        assert_eq!((four.rows(), four.cols()), (4, 4));
+        match four.solve() {
+            Solution::Complete(solved) => assert_eq!(solved.as_string().len(), 16),
+            other => panic!("expected a solution, got {:?}", other),
+        }
+        assert_eq!(four.config().layout.digits(), 4);
+
+        let mut hex = Sudoku::new(16, 16);
+        hex.set_cell(Cell::new(15, 15), 16);
+        let text = hex.as_string();
+        assert!(text.ends_with('G'));
+        assert_eq!(Sudoku::from(&text), hex);
+        assert_eq!(hex.config().layout.digits(), 16);
+    }
+
+    #[test]
+    fn sudoku_solve_budget() {
+        let sudoku = Sudoku::from(
+            "....27....1...4.....9..57...8....3..5..9..1......32...6.1....4...8....9.....4.6.5",
+        );
+        let budget = Budget {
+            advances: Some(5),
+            ..Default::default()
+        };
+        assert!(matches!(
+            sudoku.solve_budget(&budget, None),
+            Solution::Aborted(_)
+        ));
+
+        let budget = Budget {
+            guesses: Some(0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            sudoku.solve_budget(&budget, None),
+            Solution::Aborted(_)
+        ));
+
+        // A deadline only runs out on its clock.
+        let budget = Budget {
+            deadline: Some(1.0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            sudoku.solve_budget(&budget, None),
+            Solution::Complete(_)
+        ));
+        let budget = Budget {
+            clock: Some(|| 2.0),
+            ..budget
+        };
+        assert!(matches!(
+            sudoku.solve_budget(&budget, None),
+            Solution::Aborted(_)
+        ));
+        let budget = Budget::within(Duration::from_secs(0));
+        assert!(matches!(
+            sudoku.solve_budget(&budget, None),
+            Solution::Aborted(_)
+        ));
+
+        let budget = Budget {
+            advances: Some(5),
+            ..Default::default()
+        };
+        let solve = sudoku.solve_steps_budget(Config::default(), &budget);
+        assert!(solve.is_aborted());
+        assert!(!solve.end().solved);
+        assert!(solve.iter().count() < sudoku.solve_steps().iter().count());
+        assert!(Solve::from(solve.compact()).is_aborted());
+        assert!(!sudoku.solve_steps().is_aborted());
+
+        let cancel = AtomicBool::new(true);
+        match sudoku.solve_budget(&Budget::unlimited(), Some(&cancel)) {
+            Solution::Aborted(partial) => assert_eq!(partial, sudoku),
+            other => panic!("expected an aborted solve, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,315 @@
+//! Validity preserving transformations of a grid.
+//!
+//! A [`Transform`] moves cells and relabels digits. Transforms are built from
+//! the basic operations below, chained with [`Transform::then`] and undone
+//! with [`Transform::inverse`].
+
+use crate::{generate::Rng, Cell, CellOptions, Options, Sudoku};
+
+const PERMS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Transform {
+    /// Index of the source cell of every target cell.
+    cells: [u8; 81],
+    /// New label of every digit, `0` always stays empty.
+    digits: [u8; 10],
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        let mut cells = [0; 81];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = i as u8;
+        }
+        Self {
+            cells,
+            digits: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        }
+    }
+
+    /// Moves the cells, target cell `(row, col)` takes the value of `f(row, col)`.
+    fn map(f: impl Fn(usize, usize) -> (usize, usize)) -> Self {
+        let mut transform = Self::identity();
+        for (i, cell) in transform.cells.iter_mut().enumerate() {
+            let (row, col) = f(i / 9, i % 9);
+            *cell = (9 * row + col) as u8;
+        }
+        transform
+    }
+
+    /// Maps target rows and columns to source rows and columns, optionally
+    /// transposing first.
+    pub fn lines(transpose: bool, rows: [usize; 9], cols: [usize; 9]) -> Self {
+        assert!(is_line_permutation(&rows) && is_line_permutation(&cols));
+        Self::map(|row, col| {
+            if transpose {
+                (cols[col], rows[row])
+            } else {
+                (rows[row], cols[col])
+            }
+        })
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate() -> Self {
+        Self::map(|row, col| (8 - col, row))
+    }
+
+    /// Reflects in the main diagonal.
+    pub fn transpose() -> Self {
+        Self::map(|row, col| (col, row))
+    }
+
+    /// Reflects in the middle column.
+    pub fn mirror() -> Self {
+        Self::map(|row, col| (row, 8 - col))
+    }
+
+    pub fn swap_bands(a: usize, b: usize) -> Self {
+        let mut bands = [0, 1, 2];
+        bands.swap(a, b);
+        Self::permute_bands(bands)
+    }
+
+    pub fn swap_stacks(a: usize, b: usize) -> Self {
+        let mut stacks = [0, 1, 2];
+        stacks.swap(a, b);
+        Self::permute_stacks(stacks)
+    }
+
+    /// Band `i` of the result is band `bands[i]` of the source.
+    pub fn permute_bands(bands: [usize; 3]) -> Self {
+        Self::lines(
+            false,
+            lines(bands, |_| [0, 1, 2]),
+            lines([0, 1, 2], |_| [0, 1, 2]),
+        )
+    }
+
+    /// Stack `i` of the result is stack `stacks[i]` of the source.
+    pub fn permute_stacks(stacks: [usize; 3]) -> Self {
+        Self::lines(
+            false,
+            lines([0, 1, 2], |_| [0, 1, 2]),
+            lines(stacks, |_| [0, 1, 2]),
+        )
+    }
+
+    /// Row `i` of `band` in the result is row `rows[i]` of that band.
+    pub fn permute_rows(band: usize, rows: [usize; 3]) -> Self {
+        let within = |b| if b == band { rows } else { [0, 1, 2] };
+        Self::lines(
+            false,
+            lines([0, 1, 2], within),
+            lines([0, 1, 2], |_| [0, 1, 2]),
+        )
+    }
+
+    /// Column `i` of `stack` in the result is column `cols[i]` of that stack.
+    pub fn permute_cols(stack: usize, cols: [usize; 3]) -> Self {
+        let within = |s| if s == stack { cols } else { [0, 1, 2] };
+        Self::lines(
+            false,
+            lines([0, 1, 2], |_| [0, 1, 2]),
+            lines([0, 1, 2], within),
+        )
+    }
+
+    /// Digit `n` becomes `digits[n - 1]`.
+    pub fn relabel(digits: [u8; 9]) -> Self {
+        let mut seen = [false; 10];
+        for &d in &digits {
+            assert!(
+                (1..=9).contains(&d) && !seen[d as usize],
+                "not a relabelling"
+            );
+            seen[d as usize] = true;
+        }
+        let mut transform = Self::identity();
+        transform.digits[1..].copy_from_slice(&digits);
+        transform
+    }
+
+    /// A random transform, used to make a known puzzle look new.
+    pub fn random(rng: &mut Rng) -> Self {
+        let mut pick = || PERMS[rng.below(6)];
+        let (row_bands, rows) = (pick(), [pick(), pick(), pick()]);
+        let (col_stacks, cols) = (pick(), [pick(), pick(), pick()]);
+        let rows = lines(row_bands, |i| rows[i]);
+        let cols = lines(col_stacks, |i| cols[i]);
+        let mut digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        rng.shuffle(&mut digits);
+        Self::lines(rng.below(2) == 1, rows, cols).then(&Self::relabel(digits))
+    }
+
+    /// Applies `self` and then `next`.
+    pub fn then(&self, next: &Transform) -> Self {
+        let mut out = *self;
+        for (i, cell) in out.cells.iter_mut().enumerate() {
+            *cell = self.cells[next.cells[i] as usize];
+        }
+        for (d, digit) in out.digits.iter_mut().enumerate() {
+            *digit = next.digits[self.digits[d] as usize];
+        }
+        out
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut out = *self;
+        for (i, &cell) in self.cells.iter().enumerate() {
+            out.cells[cell as usize] = i as u8;
+        }
+        for (d, &digit) in self.digits.iter().enumerate() {
+            out.digits[digit as usize] = d as u8;
+        }
+        out
+    }
+
+    pub fn apply(&self, sudoku: &Sudoku) -> Sudoku {
+        let source = sudoku.inner();
+        let mut out = Sudoku::default();
+        for (i, &cell) in self.cells.iter().enumerate() {
+            out.set_cell(
+                Cell::from_index(i, 9),
+                self.digits[source[cell as usize] as usize],
+            );
+        }
+        out
+    }
+
+    pub fn apply_options(&self, options: &Options) -> Options {
+        let source = options.cells();
+        let mut out = Options::default();
+        for (i, &cell) in self.cells.iter().enumerate() {
+            let mut mapped = CellOptions::default();
+            for n in source[cell as usize].iter() {
+                mapped.add(self.digits[n as usize]);
+            }
+            *out.cell_mut(Cell::from_index(i, 9)) = mapped;
+        }
+        out
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Expands a permutation of bands and the permutation within each band.
+fn lines(outer: [usize; 3], within: impl Fn(usize) -> [usize; 3]) -> [usize; 9] {
+    let mut out = [0; 9];
+    for (i, line) in out.iter_mut().enumerate() {
+        *line = 3 * outer[i / 3] + within(i / 3)[i % 3];
+    }
+    out
+}
+
+/// Whether `lines` is a permutation that keeps every line within its band.
+fn is_line_permutation(lines: &[usize; 9]) -> bool {
+    let mut seen = [false; 9];
+    for chunk in lines.chunks(3) {
+        let source = chunk[0] / 3;
+        for &line in chunk {
+            if line >= 9 || seen[line] || line / 3 != source {
+                return false;
+            }
+            seen[line] = true;
+        }
+    }
+    true
+}
+
+/// All 1296 permutations of 9 lines that keep lines within their band.
+pub(crate) fn line_permutations() -> Vec<[usize; 9]> {
+    let mut out = Vec::with_capacity(1296);
+    for &bands in &PERMS {
+        for a in &PERMS {
+            for b in &PERMS {
+                for c in &PERMS {
+                    let within = [a, b, c];
+                    out.push(lines(bands, |i| *within[i]));
+                }
+            }
+        }
+    }
+    out
+}
+
+impl Sudoku {
+    pub fn transform(&self, transform: &Transform) -> Sudoku {
+        transform.apply(self)
+    }
+}
+
+impl Options {
+    pub fn transform(&self, transform: &Transform) -> Options {
+        transform.apply_options(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{generate::Rng, Cell, Options, Sudoku};
+
+    use super::Transform;
+
+    static SAMPLE: &str =
+        "...6..8....35.4...65..217...6..............5..7138..2...7.1.6.4.1.......9....3..7";
+
+    #[test]
+    fn transform_basic() {
+        let sudoku = Sudoku::from(SAMPLE);
+        let rotated = sudoku.transform(&Transform::rotate());
+        assert_eq!(
+            *rotated.cell(Cell::new(0, 8)),
+            *sudoku.cell(Cell::new(0, 0))
+        );
+        let full = (0..4).fold(Transform::identity(), |t, _| t.then(&Transform::rotate()));
+        assert_eq!(full, Transform::identity());
+        assert_eq!(
+            Transform::transpose().then(&Transform::mirror()),
+            Transform::rotate()
+        );
+
+        let swapped = sudoku.transform(&Transform::swap_bands(0, 2));
+        assert_eq!(
+            swapped.row(0).collect::<Vec<_>>(),
+            sudoku.row(6).collect::<Vec<_>>()
+        );
+        let relabelled = sudoku.transform(&Transform::relabel([9, 8, 7, 6, 5, 4, 3, 2, 1]));
+        assert_eq!(*relabelled.cell(Cell::new(0, 3)), 4);
+    }
+
+    #[test]
+    fn transform_compose() {
+        let sudoku = Sudoku::from(SAMPLE);
+        let mut rng = Rng::new(3);
+        let a = Transform::random(&mut rng);
+        let b = Transform::permute_rows(1, [2, 0, 1]).then(&Transform::permute_cols(2, [1, 2, 0]));
+        let both = a.then(&b);
+        assert_eq!(sudoku.transform(&both), sudoku.transform(&a).transform(&b));
+        assert_eq!(sudoku.transform(&both).transform(&both.inverse()), sudoku);
+
+        let mut options = Options::default();
+        for index in 0..81 {
+            options.options(Cell::from_index(index, 9), &sudoku);
+        }
+        let moved = sudoku.transform(&both);
+        let mut expected = Options::default();
+        for index in 0..81 {
+            expected.options(Cell::from_index(index, 9), &moved);
+        }
+        let transformed = options.transform(&both);
+        assert!(transformed.cells() == expected.cells());
+    }
+}
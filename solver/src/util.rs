@@ -1,8 +1,6 @@
-use std::fmt::Display;
-
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Cell {
     pub row: usize,
     pub col: usize,
@@ -20,10 +18,11 @@ impl Cell {
         }
     }
 
-    pub fn from_index(index: usize) -> Self {
+    /// The cell at `index` in reading order on a board `cols` wide.
+    pub fn from_index(index: usize, cols: usize) -> Self {
         Self {
-            row: index / 9,
-            col: index % 9,
+            row: index / cols,
+            col: index % cols,
         }
     }
 
@@ -31,8 +30,9 @@ impl Cell {
         3 * (self.row / 3) + self.col / 3
     }
 
-    pub fn index(&self) -> usize {
-        9 * self.row + self.col
+    /// The position in reading order on a board `cols` wide.
+    pub fn index(&self, cols: usize) -> usize {
+        cols * self.row + self.col
     }
 
     pub fn sees(&self, other: Self) -> bool {
@@ -46,17 +46,13 @@ impl Default for Cell {
     }
 }
 
-impl Display for Cell {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Cell: {{ row: {}, col: {} }}", self.row, self.col)
-    }
-}
-
 #[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Domain {
     Sqr(usize),
     Row(usize),
     Col(usize),
+    /// `0` is the main diagonal from r1c1, `1` the anti-diagonal from r1c9.
+    Diag(usize),
 }
 
 impl Domain {
@@ -65,18 +61,25 @@ impl Domain {
             Domain::Sqr(_) => SetDomain::Sqr,
             Domain::Row(_) => SetDomain::Row,
             Domain::Col(_) => SetDomain::Col,
+            Domain::Diag(_) => SetDomain::Diag,
         }
     }
 
     fn set(&self) -> usize {
         match self {
-            Domain::Sqr(n) | Domain::Row(n) | Domain::Col(n) => *n,
+            Domain::Sqr(n) | Domain::Row(n) | Domain::Col(n) | Domain::Diag(n) => *n,
         }
     }
 
     pub fn cell(&self, i: usize) -> Cell {
         self.domain().cell(self.set(), i)
     }
+
+    /// Whether `cell` lies in the domain of the standard layout. A jigsaw
+    /// piece has other cells, see [`Layout::region`](crate::Layout::region).
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.domain().is(cell, self.set())
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -84,14 +87,26 @@ pub enum SetDomain {
     Sqr,
     Row,
     Col,
+    Diag,
 }
 
 impl SetDomain {
+    pub fn at(&self, d: usize) -> Domain {
+        match self {
+            SetDomain::Sqr => Domain::Sqr(d),
+            SetDomain::Row => Domain::Row(d),
+            SetDomain::Col => Domain::Col(d),
+            SetDomain::Diag => Domain::Diag(d),
+        }
+    }
+
     pub fn cell(&self, d: usize, i: usize) -> Cell {
         match self {
             SetDomain::Sqr => Cell::from_sqr(d, i),
             SetDomain::Row => Cell::new(d, i),
             SetDomain::Col => Cell::new(i, d),
+            SetDomain::Diag if d == 0 => Cell::new(i, i),
+            SetDomain::Diag => Cell::new(i, 8 - i),
         }
     }
 
@@ -100,14 +115,18 @@ impl SetDomain {
             SetDomain::Sqr => a.sqr() == b.sqr(),
             SetDomain::Row => a.row == b.row,
             SetDomain::Col => a.col == b.col,
+            SetDomain::Diag => (0..2).any(|d| self.is(a, d) && self.is(b, d)),
         }
     }
 
+    /// Whether `c` is in domain `i` of the standard layout.
     pub fn is(&self, c: Cell, i: usize) -> bool {
         match self {
             SetDomain::Sqr => c.sqr() == i,
             SetDomain::Row => c.row == i,
             SetDomain::Col => c.col == i,
+            SetDomain::Diag if i == 0 => c.row == c.col,
+            SetDomain::Diag => c.row + c.col == 8,
         }
     }
 
@@ -116,6 +135,7 @@ impl SetDomain {
             SetDomain::Sqr => SetDomain::Sqr,
             SetDomain::Row => SetDomain::Col,
             SetDomain::Col => SetDomain::Row,
+            SetDomain::Diag => SetDomain::Diag,
         }
     }
 
@@ -124,6 +144,8 @@ impl SetDomain {
             SetDomain::Sqr => self.cell(c.sqr(), i),
             SetDomain::Row => self.cell(c.row, i),
             SetDomain::Col => self.cell(c.col, i),
+            SetDomain::Diag if self.is(c, 0) => self.cell(0, i),
+            SetDomain::Diag => self.cell(1, i),
         }
     }
 }
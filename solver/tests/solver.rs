@@ -1,4 +1,9 @@
-use solver::{solvers::Solver, sudoku::Solution, Sudoku};
+use solver::{
+    output::{CompactSolve, Solve},
+    solvers::Solver,
+    sudoku::Solution,
+    Branching, Budget, Config, Shuffle, Sudoku,
+};
 
 static INPUT: &[(&str, &str)] = &[
     (
@@ -68,3 +73,131 @@ fn solver_steps() {
         assert_eq!(solve.end().solver, Solver::Solved);
     }
 }
+
+#[test]
+fn solver_step_iter() {
+    for &(sudoku, solution) in INPUT {
+        let last = Sudoku::from(sudoku).steps().last().unwrap();
+        assert!(last.valid);
+        assert_eq!(last.solver, Solver::Solved);
+        assert_eq!(last.sudoku, Sudoku::from(solution));
+    }
+}
+
+#[test]
+fn solver_compact() {
+    for &(sudoku, _solution) in INPUT {
+        let solve = Sudoku::from(sudoku).solve_steps();
+        let compact = solve.compact();
+        assert_eq!(compact.len(), solve.iter().count());
+        for (step, expanded) in solve.iter().zip(compact.iter()) {
+            assert_eq!(step.sudoku, expanded.sudoku);
+            assert_eq!(step.cache.cells(), expanded.cache.cells());
+            assert_eq!(step.solver, expanded.solver);
+        }
+    }
+}
+
+#[test]
+fn solver_compact_round_trip() {
+    for &(sudoku, solution) in INPUT {
+        let solve = Sudoku::from(sudoku).solve_steps();
+        let compact = CompactSolve::from(solve.clone());
+        assert_eq!(compact.end().unwrap().sudoku, Sudoku::from(solution));
+        let expanded = Solve::from(compact);
+        assert_eq!(expanded.iter().count(), solve.iter().count());
+        for (step, expanded) in solve.iter().zip(expanded.iter()) {
+            assert_eq!(step.sudoku, expanded.sudoku);
+            assert_eq!(step.cache.cells(), expanded.cache.cells());
+            assert_eq!(step.solver, expanded.solver);
+            assert_eq!(step.valid, expanded.valid);
+        }
+    }
+}
+
+#[test]
+fn solver_shuffled() {
+    for &(input, output) in INPUT {
+        let sudoku = Sudoku::from(input);
+        for seed in 0..4 {
+            let config = Config {
+                shuffle: Some(Shuffle::new(seed)),
+                ..Default::default()
+            };
+            match sudoku.solve_config(config, &Budget::unlimited(), None) {
+                Solution::Complete(s) => assert_eq!(s, Sudoku::from(output)),
+                _ => panic!("{} with seed {}", input, seed),
+            }
+        }
+    }
+}
+
+#[test]
+fn solver_branching() {
+    for &branching in &[
+        Branching::Mrv,
+        Branching::HiddenSingle,
+        Branching::Degree,
+        Branching::First,
+    ] {
+        for &(input, output) in INPUT {
+            let config = Config {
+                branching,
+                ..Default::default()
+            };
+            match Sudoku::from(input).solve_config(config, &Budget::unlimited(), None) {
+                Solution::Complete(s) => assert_eq!(s, Sudoku::from(output)),
+                _ => panic!("{} with {:?}", input, branching),
+            }
+        }
+    }
+}
+
+/// Naked sets eliminate from the other cells of their regions, never from
+/// the cells of the set.
+#[test]
+fn solver_set_targets() {
+    let mut found = 0;
+    for &(sudoku, _) in INPUT {
+        for step in Sudoku::from(sudoku).solve_steps().iter() {
+            if step.solver != Solver::Set || !step.change.has_targets() {
+                continue;
+            }
+            found += 1;
+            for cell in step.sudoku.cells() {
+                assert!(
+                    !(step.change.is_target(cell) && step.change.is_source(cell)),
+                    "{}",
+                    step.explain()
+                );
+            }
+        }
+    }
+    assert!(found > 0);
+}
+
+/// A guess targets the digit it places.
+#[test]
+fn solver_guess_targets() {
+    let solve = Sudoku::from(INPUT[0].0).solve_steps();
+    let steps: Vec<_> = solve.iter().collect();
+    let guesses: Vec<_> = steps
+        .windows(2)
+        .filter(|pair| pair[0].solver == Solver::BackTrace)
+        .collect();
+    assert!(!guesses.is_empty());
+    for pair in guesses {
+        let (guess, next) = (pair[0], pair[1]);
+        let cell = guess
+            .sudoku
+            .cells()
+            .find(|&c| guess.change.is_target(c))
+            .unwrap();
+        let digit = *next.sudoku.cell(cell);
+        assert!(
+            guess.change.is_target_digit(cell, digit),
+            "{}",
+            guess.explain()
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use std::rc::Rc;
+
+use solver::{
+    sudoku::Solution, util::Domain, Arrow, Budget, Cage, Cell, Config, Edge, Inequality, Layout,
+    Mark, MultiGrid, Options, Puzzle, Sandwich, Solver, Sudoku, Thermo,
+};
+
+fn solve(sudoku: &Sudoku, layout: Layout) -> Solution {
+    let config = Config::for_layout(Rc::new(layout));
+    sudoku.solve_config(config, &Budget::unlimited(), None)
+}
+
+fn assert_regions(sudoku: &Sudoku, layout: &Layout) {
+    for region in layout.regions() {
+        let mut digits: Vec<u8> = region.cells.iter().map(|&c| *sudoku.cell(c)).collect();
+        digits.sort_unstable();
+        assert_eq!(digits, (1..=9).collect::<Vec<_>>(), "{:?}", region.domain);
+    }
+}
+
+fn assert_peers(sudoku: &Sudoku, layout: &Layout) {
+    for index in 0..81 {
+        let cell = Cell::from_index(index, 9);
+        for &peer in layout.peers(cell) {
+            assert_ne!(sudoku.cell(cell), sudoku.cell(peer), "{} {}", cell, peer);
+        }
+    }
+}
+
+/// The solved grid the variant puzzles below are built around.
+fn solution() -> Sudoku {
+    Sudoku::from(
+        "964572831172843659835961274629485713483217965517396482246138597358729146791654328",
+    )
+}
+
+/// `grid` with only the cells whose index passes `keep` left in.
+fn givens(grid: &Sudoku, keep: impl Fn(usize) -> bool) -> Sudoku {
+    let mut givens = grid.clone();
+    for index in (0..81).filter(|&i| !keep(i)) {
+        givens.set_cell(Cell::from_index(index, 9), 0);
+    }
+    givens
+}
+
+/// Solves `puzzle` step by step, checks that it is unique, that `solver`
+/// takes part and that every region ends up complete. Returns the solution.
+fn solve_variant(puzzle: &Puzzle, solver: Solver) -> Sudoku {
+    assert_eq!(puzzle.count_solutions(2), Ok(1), "{}", puzzle);
+    let config = puzzle.config().unwrap();
+    let steps = puzzle.sudoku.solve_steps_config(config.clone());
+    assert!(steps.iter().all(|s| s.valid));
+    assert!(
+        steps
+            .iter()
+            .any(|s| s.solver == solver && s.change.has_targets()),
+        "{:?}",
+        solver
+    );
+    let last = steps.iter().last().unwrap();
+    assert_eq!(last.solver, Solver::Solved);
+    assert_regions(&last.sudoku, &config.layout);
+    last.sudoku.clone()
+}
+
+#[test]
+fn variant_diagonal() {
+    let grid = match solve(&Sudoku::default(), Layout::diagonal()) {
+        Solution::Complete(grid) => grid,
+        _ => panic!("no diagonal grid"),
+    };
+    assert_regions(&grid, &Layout::diagonal());
+
+    let mut puzzle = grid;
+    for index in (0..81).step_by(3) {
+        puzzle.set_cell(Cell::from_index(index, 9), 0);
+    }
+    match solve(&puzzle, Layout::diagonal()) {
+        Solution::Complete(solved) => assert_regions(&solved, &Layout::diagonal()),
+        _ => panic!("diagonal puzzle not solved"),
+    }
+
+    let steps = puzzle.solve_steps_config(Config {
+        layout: Rc::new(Layout::diagonal()),
+        ..Default::default()
+    });
+    assert!(steps.iter().all(|s| s.valid));
+    assert!(Domain::Diag(1).contains(Cell::new(2, 6)));
+
+    let text: Puzzle = format!("{} diagonal", puzzle.as_string()).parse().unwrap();
+    assert!(text.diagonal);
+    match text
+        .sudoku
+        .solve_config(text.config().unwrap(), &Budget::unlimited(), None)
+    {
+        Solution::Complete(solved) => assert_regions(&solved, &Layout::diagonal()),
+        _ => panic!("diagonal puzzle from text not solved"),
+    }
+}
+
+#[test]
+fn variant_jigsaw() {
+    let map = "111223333111222333114222236144455536445555566444566669777888699777888999777888999";
+    let layout = Layout::jigsaw(map).unwrap();
+    let grid = match solve(&Sudoku::default(), layout.clone()) {
+        Solution::Complete(grid) => grid,
+        _ => panic!("no jigsaw grid"),
+    };
+    assert_regions(&grid, &layout);
+
+    let mut givens = grid;
+    for index in (0..81).filter(|i| i % 7 != 0) {
+        givens.set_cell(Cell::from_index(index, 9), 0);
+    }
+    let puzzle: Puzzle = format!("{} {}", givens.as_string(), map).parse().unwrap();
+    let config = puzzle.config().unwrap();
+    match puzzle
+        .sudoku
+        .solve_config(config.clone(), &Budget::unlimited(), None)
+    {
+        Solution::Complete(solved) => assert_regions(&solved, &layout),
+        _ => panic!("jigsaw puzzle not solved"),
+    }
+    let steps = puzzle.sudoku.solve_steps_config(config);
+    assert!(steps.iter().all(|s| s.valid));
+}
+
+/// Cages of horizontal pairs, with a single cell cage at the end of each row.
+fn pair_cages(grid: &Sudoku) -> Vec<Cage> {
+    let mut cages = Vec::new();
+    for row in 0..9 {
+        for col in (0..9).step_by(2) {
+            let cells: Vec<Cell> = (col..(col + 2).min(9)).map(|c| Cell::new(row, c)).collect();
+            let sum = cells.iter().map(|&c| *grid.cell(c)).sum();
+            cages.push(Cage::new(sum, cells));
+        }
+    }
+    cages
+}
+
+#[test]
+fn variant_killer() {
+    let puzzle = Puzzle {
+        sudoku: givens(&solution(), |i| i % 7 == 0),
+        cages: pair_cages(&solution()),
+        ..Default::default()
+    };
+    let solved = solve_variant(&puzzle, Solver::Cage);
+    assert_eq!(solved, solution());
+
+    // 3 in two cells is 1 + 2, the combinations are a step of their own.
+    let pair: Puzzle = format!("{} 3=r1c12", ".".repeat(81)).parse().unwrap();
+    let hint = pair
+        .sudoku
+        .hint_config(None, pair.config().unwrap())
+        .unwrap();
+    assert_eq!(hint.solver, Solver::Cage);
+    assert!((3..=9).all(|n| hint.change.is_target_option(Cell::new(0, 1), n)));
+    assert!(hint
+        .explain()
+        .to_string()
+        .starts_with("Cage Combinations r1c12"));
+
+    // Row 1 without its last cell adds up to 45 minus that cell.
+    let cage = Cage::new(45 - 1, (0..8).map(|c| Cell::new(0, c)).collect());
+    let puzzle = Puzzle {
+        cages: vec![cage],
+        ..Default::default()
+    };
+    let config = Config {
+        solvers: vec![Solver::Innies],
+        ..puzzle.config().unwrap()
+    };
+    let hint = puzzle.sudoku.hint_config(None, config).unwrap();
+    assert_eq!(hint.solver, Solver::Innies);
+    assert!((2..=9).all(|n| hint.change.is_target_option(Cell::new(0, 8), n)));
+    assert!(!hint.change.is_target_option(Cell::new(0, 8), 1));
+    assert!(!hint.change.is_target(Cell::new(0, 0)));
+    assert!(hint.explain().to_string().starts_with("Innies & Outies"));
+}
+
+#[test]
+fn variant_chess() {
+    for &(knight, king) in &[(true, false), (false, true)] {
+        let rules = Puzzle {
+            anti_knight: knight,
+            anti_king: king,
+            ..Default::default()
+        };
+        let layout = rules.layout().unwrap();
+        let grid = match solve(&Sudoku::default(), layout.clone()) {
+            Solution::Complete(grid) => grid,
+            _ => panic!("no grid for {}", rules),
+        };
+        assert_regions(&grid, &layout);
+        assert_peers(&grid, &layout);
+
+        let mut puzzle = Puzzle {
+            sudoku: grid,
+            ..rules
+        };
+        for index in (0..81).filter(|i| i % 3 != 0) {
+            puzzle.sudoku.set_cell(Cell::from_index(index, 9), 0);
+        }
+        let steps = puzzle.sudoku.solve_steps_config(puzzle.config().unwrap());
+        assert!(steps.iter().all(|s| s.valid));
+        let last = steps.iter().last().unwrap();
+        assert_eq!(last.solver, Solver::Solved);
+        assert_peers(&last.sudoku, &layout);
+    }
+}
+
+#[test]
+fn variant_samurai() {
+    let puzzle: MultiGrid = concat!(
+        "1234.6..9....467.945...91236.25.913.78...3....8.1.7....31..4..54.......8.75...364",
+        "72..6.3..6..5382.789.4.3..2.17.6.9...2.5.....4.3..28.7......2.839.6.79683..5.....",
+        ".39...825..6.5.....5.9...1....3.....3..78..1....9.4.2.5...891.3.64....5.6..14....",
+        "....93.....14..2.92.1.3..7...5.6.79...3.9....4379...82895.173.68.9..7...61..48957",
+        "2.358.97...89.1..25.8796...93.5..681.9..42.51",
+    )
+    .parse()
+    .unwrap();
+    // The middle grid needs the digits its corner boxes get from the others.
+    assert_eq!(puzzle.grid(2).count_solutions(2), 2);
+    assert_eq!(puzzle.count_solutions(2), 1);
+
+    // The whole board is one solve, without guessing.
+    let solve = puzzle.solve_steps();
+    assert!(solve.iter().all(|s| s.solver != Solver::BackTrace));
+    assert_eq!(solve.end().solver, Solver::Solved);
+    let solution = puzzle.solve().unwrap();
+    assert_eq!(solution.sudoku(), &solve.end().sudoku);
+    for g in 0..solution.grids().len() {
+        let grid = solution.grid(g);
+        assert_regions(&grid, &Layout::standard());
+        assert!(grid.is_unique());
+    }
+    let targets = || {
+        solve.iter().flat_map(|step| {
+            let cells = step.sudoku.cells();
+            cells.filter(move |&c| step.change.is_target(c))
+        })
+    };
+    for cell in targets() {
+        assert!(solution.contains(cell));
+        assert_eq!(puzzle.get(cell), 0, "{}", cell);
+    }
+    // Steps in the corner grids land on the board past the first grid.
+    assert!(targets().any(|c| c.row >= 12 && c.col >= 12));
+}
+
+/// The sum between the 1 and the 9 of each row and column of `grid`.
+fn sandwiches(grid: &Sudoku) -> Vec<Sandwich> {
+    let mut sandwiches = Vec::new();
+    for n in 0..9 {
+        for &domain in &[Domain::Row(n), Domain::Col(n)] {
+            let digits: Vec<u8> = (0..9).map(|i| *grid.cell(domain.cell(i))).collect();
+            let one = digits.iter().position(|&d| d == 1).unwrap();
+            let nine = digits.iter().position(|&d| d == 9).unwrap();
+            let sum = digits[one.min(nine) + 1..one.max(nine)].iter().sum();
+            sandwiches.push(Sandwich::new(domain, sum));
+        }
+    }
+    sandwiches
+}
+
+#[test]
+fn variant_lines() {
+    let thermo: Thermo = "thermo:r2c1,r3c23,r2c4".parse().unwrap();
+    let arrow: Arrow = "arrow:r3c1=r2c12".parse().unwrap();
+    let puzzle = Puzzle {
+        sudoku: givens(&solution(), |i| i % 7 == 0),
+        thermos: vec![thermo.clone()],
+        arrows: vec![arrow.clone()],
+        sandwiches: sandwiches(&solution()),
+        ..Default::default()
+    };
+    let solved = solve_variant(&puzzle, Solver::Sandwich);
+    assert_eq!(sandwiches(&solved), puzzle.sandwiches);
+    let digits = |cells: &[Cell]| -> Vec<u8> { cells.iter().map(|&c| *solved.cell(c)).collect() };
+    assert!(digits(&thermo.cells).windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(
+        digits(&arrow.cells).iter().sum::<u8>(),
+        *solved.cell(arrow.circle)
+    );
+
+    // With 1, 2 and 3 in column 1 the bulb is at least 4, so the next cell
+    // is at least 5. With 1 and 2 in column 6 the arrow adds up to at least 4.
+    let mut lines: Puzzle = format!("{} thermo:r1c123 arrow:r5c5=r5c67", ".".repeat(81))
+        .parse()
+        .unwrap();
+    for &(row, col, digit) in &[(3, 0, 1), (4, 0, 2), (5, 0, 3), (0, 5, 1), (1, 5, 2)] {
+        lines.sudoku.set_cell(Cell::new(row, col), digit);
+    }
+    for (solver, cell, digit) in vec![
+        (Solver::Thermo, Cell::new(0, 1), 4),
+        (Solver::Arrow, Cell::new(4, 4), 3),
+    ] {
+        let config = Config {
+            solvers: vec![solver],
+            ..lines.config().unwrap()
+        };
+        let hint = lines.sudoku.hint_config(None, config).unwrap();
+        assert_eq!(hint.solver, solver);
+        assert!(
+            hint.change.is_target_option(cell, digit),
+            "{}",
+            hint.explain()
+        );
+    }
+}
+
+/// A mark on every pair of neighbours in `grid` that has a relation.
+fn edge_marks(grid: &Sudoku) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for index in 0..81 {
+        let a = Cell::from_index(index, 9);
+        for &b in &[Cell::new(a.row, a.col + 1), Cell::new(a.row + 1, a.col)] {
+            if b.row > 8 || b.col > 8 {
+                continue;
+            }
+            let (x, y) = (*grid.cell(a), *grid.cell(b));
+            let mark = [Mark::White, Mark::Black, Mark::X, Mark::V]
+                .iter()
+                .find(|m| m.allows(x, y));
+            if let Some(&mark) = mark {
+                edges.push(Edge::new(a, b, mark));
+            }
+        }
+    }
+    edges
+}
+
+#[test]
+fn variant_edges() {
+    let puzzle = Puzzle {
+        sudoku: givens(&solution(), |i| i % 9 == 0),
+        edges: edge_marks(&solution()),
+        negative: vec![Mark::White, Mark::Black, Mark::X, Mark::V],
+        ..Default::default()
+    };
+    let solved = solve_variant(&puzzle, Solver::Edges);
+    assert_eq!(edge_marks(&solved), puzzle.edges);
+
+    // The two cells of a V can't be more than 4.
+    let v: Puzzle = format!("{} v:r1c12", ".".repeat(81)).parse().unwrap();
+    let config = Config {
+        solvers: vec![Solver::Edges],
+        ..v.config().unwrap()
+    };
+    let hint = v.sudoku.hint_config(None, config).unwrap();
+    assert!((5..=9).all(|n| hint.change.is_target_option(Cell::new(0, 0), n)));
+    assert!(hint.explain().to_string().starts_with("Kropki & XV"));
+}
+
+#[test]
+fn variant_signs() {
+    let grid = solution();
+    let mut puzzle = Puzzle::new(givens(&grid, |i| i % 3 == 0));
+    for index in 0..81 {
+        let a = Cell::from_index(index, 9);
+        if a.col < 8 {
+            let b = Cell::new(a.row, a.col + 1);
+            puzzle
+                .inequalities
+                .push(match *grid.cell(a) > *grid.cell(b) {
+                    true => Inequality::new(a, b),
+                    false => Inequality::new(b, a),
+                });
+        }
+        if a.row % 3 == 0 {
+            match *grid.cell(a) % 2 {
+                1 => puzzle.odd.push(a),
+                _ => puzzle.even.push(a),
+            }
+        }
+    }
+
+    // Shaded cells start with only the digits of their parity.
+    let options = Options::for_layout(&puzzle.layout().unwrap());
+    assert_eq!(options.cell(puzzle.odd[0]).len(), 5);
+    assert_eq!(options.cell(puzzle.even[0]).len(), 4);
+    assert_eq!(options.cell(Cell::new(1, 0)).len(), 9);
+
+    let solved = solve_variant(&puzzle, Solver::Inequality);
+    let digit = |cell: Cell| *solved.cell(cell);
+    assert!(puzzle
+        .inequalities
+        .iter()
+        .all(|s| digit(s.greater) > digit(s.less)));
+    assert!(puzzle.odd.iter().all(|&c| digit(c) % 2 == 1));
+    assert!(puzzle.even.iter().all(|&c| digit(c) % 2 == 0));
+
+    // r1c1 is above r1c2, which is above r1c3. The first step takes 1 from
+    // r1c1, once r1c2 is at least 2 a later one takes 2 as well.
+    let chain: Puzzle = format!("{} r1c1>r1c2 r1c2>r1c3", ".".repeat(81))
+        .parse()
+        .unwrap();
+    let config = Config {
+        solvers: vec![Solver::Inequality],
+        ..chain.config().unwrap()
+    };
+    let hint = chain.sudoku.hint_config(None, config.clone()).unwrap();
+    assert!(hint.change.is_target_option(Cell::new(0, 0), 1));
+    assert!(hint.explain().to_string().starts_with("Greater Than"));
+    let mut hints = vec![hint];
+    while let Some(hint) = {
+        let (sudoku, options) = hints.last().unwrap().applied();
+        sudoku.hint_config(Some(&options), config.clone())
+    } {
+        hints.push(hint);
+    }
+    assert!(hints
+        .iter()
+        .all(|h| h.solver == Solver::Inequality && h.change.has_targets()));
+    assert!(hints
+        .iter()
+        .any(|h| h.change.is_target_option(Cell::new(0, 0), 2)));
+}
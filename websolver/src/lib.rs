@@ -5,7 +5,12 @@ mod ui;
 mod util;
 
 #[cfg(feature = "worker")]
-use solver::Sudoku;
+use std::rc::Rc;
+
+#[cfg(feature = "worker")]
+use serde::Deserialize;
+#[cfg(feature = "worker")]
+use solver::{Budget, Config, Puzzle, StepIter, Sudoku};
 
 use wasm_bindgen::prelude::*;
 
@@ -28,12 +33,74 @@ pub fn run() -> Result<(), JsValue> {
     Ok(())
 }
 
+/// A puzzle as sent to the worker, either a plain sudoku or a sudoku with
+/// its layout.
 #[cfg(feature = "worker")]
-#[wasm_bindgen]
-pub fn solve(sudoku: &JsValue) -> Result<JsValue, JsValue> {
-    let s: Sudoku = sudoku
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PuzzleData {
+    Puzzle(Puzzle),
+    Sudoku(Sudoku),
+}
+
+#[cfg(feature = "worker")]
+fn puzzle(value: &JsValue) -> Result<(Sudoku, Config), JsValue> {
+    let puzzle = match value
         .into_serde()
+        .map_err(|e| JsValue::from_str(&format!("{}", e)))?
+    {
+        PuzzleData::Puzzle(puzzle) => puzzle,
+        PuzzleData::Sudoku(sudoku) => Puzzle::new(sudoku),
+    };
+    let config = puzzle
+        .config()
         .map_err(|e| JsValue::from_str(&format!("{}", e)))?;
-    let solve = s.solve_steps();
-    JsValue::from_serde(&solve).map_err(|e| JsValue::from_str(&format!("{}", e)))
+    Ok((puzzle.sudoku, config))
+}
+
+/// Solves `sudoku`, a plain sudoku or a [`Puzzle`] with its layout, returning
+/// the steps in their compact, delta encoded form when `compact` is set.
+///
+/// The solve stops after `advances` steps of the search, `guesses` guesses or
+/// `millis` milliseconds, whichever comes first, so a hard puzzle can't
+/// freeze the worker.
+#[cfg(feature = "worker")]
+#[wasm_bindgen]
+pub fn solve(
+    sudoku: &JsValue,
+    compact: Option<bool>,
+    advances: Option<u32>,
+    guesses: Option<u32>,
+    millis: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let (s, config) = puzzle(sudoku)?;
+    let budget = Budget {
+        advances: advances.map(u64::from),
+        guesses,
+        deadline: millis.map(|ms| js_sys::Date::now() + ms),
+        clock: Some(js_sys::Date::now),
+    };
+    let solve = s.solve_steps_budget(config, &budget);
+    if compact.unwrap_or(false) {
+        JsValue::from_serde(&solve.compact())
+    } else {
+        JsValue::from_serde(&solve)
+    }
+    .map_err(|e| JsValue::from_str(&format!("{}", e)))
+}
+
+/// Streams the steps of a solve to `callback` one at a time, stopping early
+/// when the callback returns `false`.
+#[cfg(feature = "worker")]
+#[wasm_bindgen]
+pub fn solve_stream(sudoku: &JsValue, callback: &js_sys::Function) -> Result<(), JsValue> {
+    let (s, config) = puzzle(sudoku)?;
+    let this = JsValue::null();
+    for step in StepIter::new(s, Rc::new(config)) {
+        let step = JsValue::from_serde(&step).map_err(|e| JsValue::from_str(&format!("{}", e)))?;
+        if callback.call1(&this, &step)? == JsValue::FALSE {
+            break;
+        }
+    }
+    Ok(())
 }
@@ -4,9 +4,27 @@ use wasm_bindgen::prelude::*;
 use crate::util::{InitCell, Measure};
 
 use super::{controller::app::AppController, view::app::AppElement};
-use solver::{Solve, Sudoku};
+use serde::Deserialize;
+use solver::{CompactSolve, Puzzle, Solve, Sudoku};
 use webelements::{document, WebElementBuilder};
 
+/// A solve as sent by the worker, in either of its serialized forms.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SolveData {
+    Full(Solve),
+    Compact(CompactSolve),
+}
+
+impl From<SolveData> for Solve {
+    fn from(data: SolveData) -> Self {
+        match data {
+            SolveData::Full(solve) => solve,
+            SolveData::Compact(compact) => Solve::from(compact),
+        }
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Debug)]
 pub struct App {
@@ -46,13 +64,32 @@ impl App {
         Ok(())
     }
 
+    /// Loads a puzzle in its text form, the givens optionally followed by
+    /// variant rules, see [`Puzzle`].
+    pub fn load(&self, puzzle: &str) -> Result<(), JsValue> {
+        let puzzle: Puzzle = puzzle
+            .parse()
+            .map_err(|e| JsValue::from_str(&format!("{}", e)))?;
+        {
+            let mut model = self.controller.sudoku.state.borrow_mut();
+            model.set_start(puzzle.sudoku);
+            model
+                .set_rules(puzzle)
+                .map_err(|e| JsValue::from_str(&format!("{}", e)))?;
+            model.clear_state();
+            self.controller.info.info.borrow_mut().clear_solve()?;
+        }
+        self.controller.update()?;
+        Ok(())
+    }
+
     pub fn set_solver(&self, f: &js_sys::Function) {
         self.controller.sudoku.set_solver(f);
     }
 
     pub fn on_solve(&self, solve: JsValue) -> Result<(), JsValue> {
-        let solve: Solve = solve.into_serde().unwrap();
-        self.controller.sudoku.on_solve(solve)?;
+        let solve: SolveData = solve.into_serde().unwrap();
+        self.controller.sudoku.on_solve(Solve::from(solve))?;
         Ok(())
     }
 
@@ -1,7 +1,10 @@
 use std::convert::TryInto;
 
 use crate::{
-    ui::editor::{Editor, EditorAction},
+    ui::{
+        editor::{Editor, EditorAction},
+        sudoku::SudokuModel,
+    },
     util::InitCell,
 };
 
@@ -53,6 +56,23 @@ impl EditorController {
                 let mut model = self.app.sudoku.state.borrow_mut();
                 let mut info = self.app.info.info.borrow_mut();
                 match action {
+                    EditorAction::Hint => {
+                        // Continue from the current state and pencil marks.
+                        let sudoku = model.state().unwrap_or_else(|| model.start()).get().clone();
+                        match sudoku.hint_config(model.options(), model.config()) {
+                            Some(step) => {
+                                let (sudoku, options) = step.applied();
+                                model.set_state(SudokuModel::from(sudoku));
+                                model.set_options(options);
+                                info.set_solve(std::iter::once(step).collect())?;
+                                info.set_step(0)?;
+                            }
+                            None => {
+                                model.clear_state();
+                                info.clear_solve()?;
+                            }
+                        }
+                    }
                     EditorAction::Erase => {
                         model.clear_state();
                         info.clear_solve()?;
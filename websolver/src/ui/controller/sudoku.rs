@@ -94,11 +94,10 @@ impl SudokuController {
 
     pub fn solve(&self) {
         let model = self.state.borrow();
-        let start = model.start();
         if let Some(solver) = self.solver.borrow().as_ref() {
             let this = JsValue::null();
             solver
-                .call1(&this, &JsValue::from_serde(start.get()).unwrap())
+                .call1(&this, &JsValue::from_serde(&model.puzzle()).unwrap())
                 .unwrap();
         }
     }
@@ -109,7 +108,8 @@ impl SudokuController {
             let mut info = self.app.info.info.borrow_mut();
 
             let step = solve.iter().last().unwrap();
-            model.set_state(SudokuModel::from(step.sudoku));
+            model.clear_state();
+            model.set_state(SudokuModel::from(step.sudoku.clone()));
             info.set_solve(solve)?;
             let max = info.max();
             info.set_step(max)?;
@@ -2,6 +2,7 @@
 pub enum EditorAction {
     SetValue(u8),
     Solve,
+    Hint,
     Erase,
     Clear,
     First,
@@ -28,6 +29,7 @@ impl std::fmt::Display for EditorAction {
                 }
             }
             EditorAction::Solve => "Solve".to_string(),
+            EditorAction::Hint => "Hint".to_string(),
             EditorAction::Erase => "Erase".to_string(),
             EditorAction::Clear => "Clear".to_string(),
             EditorAction::First => "<<".to_string(),
@@ -1,4 +1,4 @@
-use solver::{output::SolveStep, solvers::Solver, Options, Solve, StateMod, Sudoku};
+use solver::{output::SolveStep, solvers::Solver, Options, Rating, Solve, StateMod, Sudoku};
 
 use webelements::Result;
 
@@ -7,10 +7,12 @@ use crate::util::Measure;
 #[derive(Debug, Clone, Copy)]
 pub enum Stat {
     Tech,
+    Explain,
     Steps,
     Guesses,
     GSteps,
     GTotal,
+    Difficulty,
     None,
 }
 
@@ -30,6 +32,7 @@ struct StateModStep<'a> {
 pub struct SudokuInfo {
     measure: Option<Measure>,
     solve: Option<Solve>,
+    rating: Option<Rating>,
     step: usize,
     s_step: Option<SolveStep>,
     max: usize,
@@ -51,6 +54,7 @@ impl SudokuInfo {
     pub fn set_solve(&mut self, s: Solve) -> Result<()> {
         let max = s.iter().count().saturating_sub(1);
         self.max = max;
+        self.rating = Some(Rating::from(&s));
         self.solve = Some(s);
         self.update_properties()?;
         Ok(())
@@ -58,6 +62,7 @@ impl SudokuInfo {
 
     pub fn clear_solve(&mut self) -> Result<()> {
         self.solve.take();
+        self.rating.take();
         self.s_step.take();
         self.max = 0;
         self.step = 0;
@@ -65,6 +70,10 @@ impl SudokuInfo {
         Ok(())
     }
 
+    pub fn rating(&self) -> Option<&Rating> {
+        self.rating.as_ref()
+    }
+
     pub fn max(&self) -> usize {
         self.max
     }
@@ -108,6 +117,7 @@ impl SudokuInfo {
         if let Some(step) = solve_step {
             match stat {
                 Stat::Tech => Some(step.solver.to_string()),
+                Stat::Explain => Some(step.explain().to_string()),
                 Stat::Steps => self
                     .solve()
                     .as_ref()
@@ -120,6 +130,7 @@ impl SudokuInfo {
                     .as_ref()
                     .map(|s| s.iter().filter(|t| t.solver == Solver::BackTrace).count())
                     .map(|c| format!("{}", c)),
+                Stat::Difficulty => self.rating().map(|r| format!("{} ({})", r.grade, r.score)),
                 _ => None,
             }
         } else {
@@ -133,6 +144,7 @@ impl Default for SudokuInfo {
         Self {
             measure: None,
             solve: None,
+            rating: None,
             step: 0,
             s_step: None,
             max: 0,
@@ -1,10 +1,17 @@
-use solver::{Cell, Sudoku};
+use std::rc::Rc;
+
+use solver::{layout::LayoutError, Cell, Config, Layout, Options, Puzzle, Sudoku};
 
 #[derive(Debug)]
 pub struct SudokuStateModel {
     pub start: SudokuModel,
     pub state: Option<SudokuModel>,
+    /// Pencil marks of `state`, the player's remaining candidates.
+    options: Option<Options>,
     selected: Option<Cell>,
+    /// The variant rules, the sudoku of `rules` is not used.
+    rules: Puzzle,
+    layout: Rc<Layout>,
 }
 
 impl SudokuStateModel {
@@ -24,6 +31,41 @@ impl SudokuStateModel {
         self.start.clear()
     }
 
+    pub fn rules(&self) -> &Puzzle {
+        &self.rules
+    }
+
+    /// Switches to the layout and constraints of `rules`. Invalid rules
+    /// leave the current ones unchanged.
+    pub fn set_rules(&mut self, rules: Puzzle) -> Result<(), LayoutError> {
+        self.layout = if rules.is_standard() {
+            Layout::shared()
+        } else {
+            Rc::new(rules.layout()?)
+        };
+        self.rules = Puzzle {
+            sudoku: Sudoku::default(),
+            ..rules
+        };
+        Ok(())
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// The start sudoku together with its layout.
+    pub fn puzzle(&self) -> Puzzle {
+        Puzzle {
+            sudoku: self.start.get().clone(),
+            ..self.rules.clone()
+        }
+    }
+
+    pub fn config(&self) -> Config {
+        Config::for_layout(Rc::clone(&self.layout))
+    }
+
     pub fn state(&self) -> Option<&SudokuModel> {
         self.state.as_ref()
     }
@@ -34,6 +76,15 @@ impl SudokuStateModel {
 
     pub fn clear_state(&mut self) {
         self.state.take();
+        self.options.take();
+    }
+
+    pub fn options(&self) -> Option<&Options> {
+        self.options.as_ref()
+    }
+
+    pub fn set_options(&mut self, options: Options) {
+        self.options.replace(options);
     }
 
     pub fn selected(&self) -> Option<Cell> {
@@ -54,7 +105,10 @@ impl Default for SudokuStateModel {
         Self {
             start: Default::default(),
             state: None,
+            options: None,
             selected: None,
+            rules: Puzzle::default(),
+            layout: Layout::shared(),
         }
     }
 }
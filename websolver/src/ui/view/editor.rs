@@ -74,6 +74,7 @@ impl NumberBar {
 #[we_builder(
     <div class="btn-panel solve-options">
         <EditorButton we_field="solve" we_element />
+        <EditorButton we_field="hint" we_element />
         <EditorButton we_field="erase" we_element />
         <EditorButton we_field="clear" we_element />
     </div>
@@ -84,6 +85,7 @@ pub struct OptionBar {}
 impl WebElement for OptionBar {
     fn init(&mut self) -> Result<()> {
         self.solve.action = EditorAction::Solve;
+        self.hint.action = EditorAction::Hint;
         self.erase.action = EditorAction::Erase;
         self.clear.action = EditorAction::Clear;
         Ok(())
@@ -93,6 +95,7 @@ impl WebElement for OptionBar {
 impl OptionBar {
     pub fn connect(&self, editor: InitCell<EditorController>) -> Result<()> {
         self.solve.connect(InitCell::clone(&editor))?;
+        self.hint.connect(InitCell::clone(&editor))?;
         self.erase.connect(InitCell::clone(&editor))?;
         self.clear.connect(InitCell::clone(&editor))?;
         Ok(())
@@ -100,6 +103,7 @@ impl OptionBar {
 
     pub fn update(&self, editor: &EditorController) {
         self.solve.update(editor);
+        self.hint.update(editor);
         self.erase.update(editor);
         self.clear.update(editor);
     }
@@ -197,7 +201,9 @@ impl StepSlider {
             self.slider.set_min(0);
             self.slider.set_max(info.max());
             self.slider.set_value(info.step());
-            webelements::log(format!("{:?}", info.step()));
+            if let Some(step) = info.solve_step() {
+                webelements::log(format!("step {}: {}", info.step(), step.explain()));
+            }
             self.slider.del_attr("disabled")?;
         }
         Ok(())
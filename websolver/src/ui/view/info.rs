@@ -15,6 +15,8 @@ use webelements::{we_builder, Result, WebElement};
         <InfoStat we_field="guesses" we_element />
         <InfoStat we_field="g_steps" we_element />
         <InfoStat we_field="g_total" we_element />
+        <InfoStat we_field="difficulty" we_element />
+        <InfoStat we_field="explain" we_element />
     </div>
 )]
 #[derive(Debug, Clone)]
@@ -27,6 +29,8 @@ impl WebElement for Info {
         self.guesses.stat = Stat::Guesses;
         self.g_steps.stat = Stat::GSteps;
         self.g_total.stat = Stat::GTotal;
+        self.difficulty.stat = Stat::Difficulty;
+        self.explain.stat = Stat::Explain;
 
         Ok(())
     }
@@ -43,6 +47,8 @@ impl Info {
         self.guesses.update(info)?;
         self.g_steps.update(info)?;
         self.g_total.update(info)?;
+        self.difficulty.update(info)?;
+        self.explain.update(info)?;
         Ok(())
     }
 }
@@ -67,6 +73,8 @@ impl InfoStat {
             Stat::Guesses => "Guesses",
             Stat::GSteps => "Total Steps",
             Stat::GTotal => "Total Guesses",
+            Stat::Explain => "Step",
+            Stat::Difficulty => "Difficulty",
             _ => "N/A",
         };
         self.label.set_text(format!("{}:", stat));
@@ -1,4 +1,4 @@
-use solver::Cell;
+use solver::{util::Domain, Cell, Layout};
 
 use crate::{
     ui::controller::{app::AppController, sudoku::SudokuController},
@@ -18,7 +18,7 @@ pub struct Sudoku {}
 impl WebElement for Sudoku {
     fn init(&mut self) -> Result<()> {
         for (index, cell) in self.cells.iter_mut().enumerate() {
-            cell.set_cell(Cell::from_index(index));
+            cell.set_cell(Cell::from_index(index, 9));
         }
         Ok(())
     }
@@ -64,6 +64,7 @@ impl CellBox {
         self.cell = cell;
         self.options.cell = cell;
         self.indicator.cell = cell;
+        self.cage.cell = cell;
     }
 
     pub fn update(&self, sudoku: &SudokuController) {
@@ -76,7 +77,31 @@ impl CellBox {
         let value = model.start().cell(self.cell);
         debug_assert!(value <= 9, "invalid cell value {}", value);
         self.number.remove_class("starting state empty");
-        self.remove_class("target source selected");
+        self.remove_class(
+            "target source selected diagonal main-diagonal anti-diagonal edge-left edge-bottom odd even",
+        );
+
+        // Thick borders between boxes, or between the pieces of a jigsaw.
+        let layout = model.layout();
+        let piece = layout.box_of(self.cell);
+        let (row, col) = (self.cell.row, self.cell.col);
+        if col > 0 && layout.box_of(Cell::new(row, col - 1)) != piece {
+            self.add_class("edge-left");
+        }
+        if row < 8 && layout.box_of(Cell::new(row + 1, col)) != piece {
+            self.add_class("edge-bottom");
+        }
+        if layout.in_region(Domain::Diag(0), self.cell) {
+            self.add_class("main-diagonal");
+        }
+        if layout.in_region(Domain::Diag(1), self.cell) {
+            self.add_class("anti-diagonal");
+        }
+        if let Some(parity) = layout.parity_of(self.cell) {
+            self.add_class(&parity.to_string());
+        }
+        self.cage.update(layout);
+        self.indicator.update(layout);
 
         if info.solve().is_some() {
             self.options.remove_class("hidden");
@@ -106,6 +131,13 @@ impl CellBox {
             } else if step.change.is_source(self.cell) {
                 self.add_class("source");
             }
+            if step
+                .change
+                .marked_domains(self.cell, layout)
+                .any(|d| matches!(d, Domain::Diag(_)))
+            {
+                self.add_class("diagonal");
+            }
         }
 
         if let Some(selected) = model.selected() {
@@ -120,10 +152,10 @@ impl CellBox {
 
 #[we_builder(
     <div class="cell-indicator">
-        <div class="indicator top" />
-        <div class="indicator left" />
-        <div class="indicator right" />
-        <div class="indicator bottom" />
+        <div class="indicator top" we_field="top" />
+        <div class="indicator left" we_field="left" />
+        <div class="indicator right" we_field="right" />
+        <div class="indicator bottom" we_field="bottom" />
     </div>
 )]
 #[derive(Debug, Clone, WebElement)]
@@ -131,12 +163,40 @@ pub struct Indicator {
     cell: Cell,
 }
 
+impl Indicator {
+    /// Shows the Kropki dot, XV mark or greater-than sign on the right and
+    /// bottom sides of the cell, so every edge is drawn once by the cell left
+    /// of or above it.
+    fn update(&self, layout: &Layout) {
+        let (row, col) = (self.cell.row, self.cell.col);
+        let sides = [(&self.right, row, col + 1), (&self.bottom, row + 1, col)];
+        for (side, r, c) in sides.iter() {
+            side.remove_class("mark-white mark-black mark-x mark-v greater less");
+            if *r > 8 || *c > 8 {
+                continue;
+            }
+            let other = Cell::new(*r, *c);
+            if let Some(edge) = layout.edge(self.cell, other) {
+                side.add_class(&format!("mark-{}", edge.mark));
+            }
+            if let Some(sign) = layout.inequality(self.cell, other) {
+                side.add_class(if sign.greater == self.cell {
+                    "greater"
+                } else {
+                    "less"
+                });
+            }
+        }
+    }
+}
+
 #[we_builder(
     <div class="cell-cage">
-        <div class="cage top" />
-        <div class="cage left" />
-        <div class="cage right" />
-        <div class="cage bottom" />
+        <div class="cage top" we_field="top" />
+        <div class="cage left" we_field="left" />
+        <div class="cage right" we_field="right" />
+        <div class="cage bottom" we_field="bottom" />
+        <div class="cage-sum" we_field="sum" />
     </div>
 )]
 #[derive(Debug, Clone, WebElement)]
@@ -144,6 +204,44 @@ pub struct Cage {
     cell: Cell,
 }
 
+impl Cage {
+    /// Draws the outline of the killer cage of the cell on the sides that
+    /// face out of the cage. The first cell of a cage shows its sum.
+    fn update(&self, layout: &Layout) {
+        let cage = match layout.cage_of(self.cell) {
+            Some(cage) => cage,
+            None => {
+                self.add_class("hidden");
+                return;
+            }
+        };
+        self.remove_class("hidden");
+        let (row, col) = (self.cell.row as isize, self.cell.col as isize);
+        let sides = [
+            (&self.top, -1, 0),
+            (&self.left, 0, -1),
+            (&self.right, 0, 1),
+            (&self.bottom, 1, 0),
+        ];
+        for (side, dr, dc) in sides.iter() {
+            let (r, c) = (row + dr, col + dc);
+            let inside = (0..9).contains(&r)
+                && (0..9).contains(&c)
+                && cage.contains(Cell::new(r as usize, c as usize));
+            if inside {
+                side.add_class("hidden");
+            } else {
+                side.remove_class("hidden");
+            }
+        }
+        if cage.cells.iter().min_by_key(|c| c.index()) == Some(&self.cell) {
+            self.sum.set_text(&format!("{}", cage.sum));
+        } else {
+            self.sum.set_text("");
+        }
+    }
+}
+
 #[we_builder(
     <div class="cell-options">
         <div class="cell-option" we_field="options" we_repeat="9" />
@@ -167,15 +265,19 @@ impl WebElement for Options {
 impl Options {
     fn update(&self, sudoku: &SudokuController) {
         let info = sudoku.app.info.info.borrow();
+        let model = sudoku.state.borrow();
         for (option, e) in self.options.iter().enumerate() {
             if let Some(step) = info.solve_step() {
                 let index = option as u8 + 1;
-                let mut cache = step.cache;
+                let mut cache = step.cache.clone();
                 e.remove_class("target");
                 e.remove_class("source");
                 e.remove_class("hidden");
                 e.remove_class("digit");
-                if !cache.options(self.cell, &step.sudoku).has(index) {
+                if !cache
+                    .options_in(self.cell, &step.sudoku, model.layout())
+                    .has(index)
+                {
                     e.add_class("hidden");
                 }
                 if let Some(step) = info.solve_step() {